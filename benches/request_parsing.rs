@@ -0,0 +1,117 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compare the `serde_json::Value` based parsing with the single-pass parsing.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use eth_jsonrpc_lib::rpc_complete::complete::Complete;
+use eth_jsonrpc_lib::rpc_request::{PartialRequest, RawRpcRequest, Request, ValidationMode};
+use serde_json::Value;
+
+const ETH_CALL: &str = r#"{
+    "jsonrpc": "2.0",
+    "id": 1,
+    "method": "eth_call",
+    "params": [
+        {
+            "from": "0x6f46cf5569aefa1acc1009290c8e043747172d89",
+            "to": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "gas": "0x76c0",
+            "gasPrice": "0x9184e72a000",
+            "value": "0x0",
+            "data": "0x70a082310000000000000000000000006f46cf5569aefa1acc1009290c8e043747172d89"
+        },
+        "latest"
+    ]
+}"#;
+
+const ETH_GET_LOGS: &str = r#"{
+    "jsonrpc": "2.0",
+    "id": 2,
+    "method": "eth_getLogs",
+    "params": [
+        {
+            "fromBlock": "0x10d4f",
+            "toBlock": "latest",
+            "address": [
+                "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+            ],
+            "topics": [
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                null,
+                [
+                    "0x0000000000000000000000006f46cf5569aefa1acc1009290c8e043747172d89",
+                    "0x000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+                ]
+            ]
+        }
+    ]
+}"#;
+
+fn batch_body() -> String {
+    let entries: Vec<&str> = (0..50)
+        .map(|i| if i % 2 == 0 { ETH_CALL } else { ETH_GET_LOGS })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+// The parsing path through `serde_json::Value`, as `RpcRequest` was
+// deserialized before the single-pass parsing: the body is read into a
+// `Value`, tried as a batch, then as a single request.
+fn parse_old(body: &str) -> Vec<Request> {
+    let v: Value = serde_json::from_str(body).unwrap();
+    let reqs: Vec<PartialRequest> = serde_json::from_value(v.clone())
+        .or_else(|_| serde_json::from_value(v).map(|req| vec![req]))
+        .unwrap();
+    reqs.into_iter()
+        .map(|req| req.complete().unwrap())
+        .collect()
+}
+
+// The single-pass parsing path.
+fn parse_new(body: &str) -> Vec<Request> {
+    match RawRpcRequest::parse(body, ValidationMode::Lenient).unwrap() {
+        RawRpcRequest::Single(req) => vec![req.complete().unwrap()],
+        RawRpcRequest::Batch(entries) => entries
+            .into_iter()
+            .map(|entry| {
+                ValidationMode::Lenient
+                    .parse_request(entry.get())
+                    .unwrap()
+                    .complete()
+                    .unwrap()
+            })
+            .collect(),
+    }
+}
+
+fn bench_parsing(c: &mut Criterion) {
+    let batch = batch_body();
+    let payloads = [
+        ("eth_call", ETH_CALL),
+        ("eth_getLogs", ETH_GET_LOGS),
+        ("batch", batch.as_str()),
+    ];
+    for (name, body) in payloads.iter() {
+        assert_eq!(parse_old(body), parse_new(body));
+        let mut group = c.benchmark_group(*name);
+        group.bench_function("value", |b| b.iter(|| parse_old(black_box(body))));
+        group.bench_function("single_pass", |b| b.iter(|| parse_new(black_box(body))));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_parsing);
+criterion_main!(benches);
@@ -19,25 +19,37 @@ extern crate syn;
 #[macro_use]
 extern crate quote;
 
-use syn::parse::{Parse, ParseStream};
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::token;
 
 struct TypeWithAttrs {
     attrs: Vec<syn::Attribute>,
+    name: Option<syn::Ident>,
     typ: syn::Type,
 }
 
 impl Parse for TypeWithAttrs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        // The param name is optional, as `address: Data20` or `Data20`.
+        let name = if input.peek(syn::Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+            let name = input.parse()?;
+            let _: Token![:] = input.parse()?;
+            Some(name)
+        } else {
+            None
+        };
         Ok(Self {
-            attrs: input.call(syn::Attribute::parse_outer)?,
+            attrs,
+            name,
             typ: input.parse()?,
         })
     }
 }
 
 struct ParamsType {
+    attrs: Vec<syn::Attribute>,
     name: syn::Ident,
     colon_token: Token![:],
     brace_token: token::Bracket,
@@ -50,6 +62,7 @@ impl Parse for ParamsType {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let content;
         Ok(ParamsType {
+            attrs: input.call(syn::Attribute::parse_outer)?,
             name: input.parse()?,
             colon_token: input.parse()?,
             brace_token: bracketed!(content in input),
@@ -73,17 +86,158 @@ fn construct_rpcname_from_params_name(params_name: &str) -> syn::LitStr {
     syn::LitStr::new(&rpcname, proc_macro2::Span::call_site())
 }
 
+struct MethodEntry {
+    enum_name: syn::Ident,
+    params: ParamsType,
+}
+
+impl Parse for MethodEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let attrs = content.call(syn::Attribute::parse_outer)?;
+        let enum_name = content.parse()?;
+        let _: Token![,] = content.parse()?;
+        let mut params: ParamsType = content.parse()?;
+        params.attrs.extend(attrs);
+        Ok(MethodEntry { enum_name, params })
+    }
+}
+
+struct HandlerDefault {
+    enum_name: syn::Ident,
+    default: syn::Path,
+}
+
+impl Parse for HandlerDefault {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let enum_name = input.parse()?;
+        let _: Token![=>] = input.parse()?;
+        Ok(HandlerDefault {
+            enum_name,
+            default: input.parse()?,
+        })
+    }
+}
+
+// The input of `construct_handler`, the `defaults { EnumName => path, }` block is
+// optional, and the listed methods use the function as the default implementation.
+struct HandlerInput {
+    defaults: Vec<HandlerDefault>,
+    entries: Punctuated<MethodEntry, Token![,]>,
+}
+
+impl Parse for HandlerInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut defaults = Vec::new();
+        if input.peek(syn::Ident) && input.peek2(token::Brace) {
+            let keyword: syn::Ident = input.parse()?;
+            if keyword != "defaults" {
+                return Err(syn::Error::new(keyword.span(), "expected `defaults`"));
+            }
+            let content;
+            braced!(content in input);
+            let list = content.parse_terminated::<_, Token![,]>(HandlerDefault::parse)?;
+            defaults.extend(list);
+        }
+        Ok(HandlerInput {
+            defaults,
+            entries: input.parse_terminated(MethodEntry::parse)?,
+        })
+    }
+}
+
+// Get the namespace from JSON-RPC name, such as `eth` of `eth_getBalance`,
+// or `rpc` of `rpc.discover`.
+// The CITA native methods have no prefix, and their namespace is `cita`.
+fn construct_namespace_from_rpcname(rpcname: &str) -> String {
+    match rpcname.split_once(|c| c == '_' || c == '.') {
+        Some((prefix, _))
+            if prefix
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()) =>
+        {
+            prefix.to_owned()
+        }
+        _ => "cita".to_owned(),
+    }
+}
+
+// The required params are the ones without `#[serde(default)]`.
+fn required_len(fields: &Punctuated<TypeWithAttrs, Token![,]>) -> usize {
+    fields.iter().filter(|field| field.attrs.is_empty()).count()
+}
+
+// Get the handler method name from the enum name.
+// Both `GetBlockByHash` and `eth_getBlockByHash` are converted to snake case,
+// as `get_block_by_hash` and `eth_get_block_by_hash`.
+fn construct_handler_name_from_enum_name(enum_name: &str) -> syn::Ident {
+    let mut name = String::with_capacity(enum_name.len() + 4);
+    let mut prev_is_lower = false;
+    for c in enum_name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_is_lower {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+            prev_is_lower = false;
+        } else {
+            name.push(c);
+            prev_is_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        }
+    }
+    syn::Ident::new(&name, proc_macro2::Span::call_site())
+}
+
+// Get JSON-RPC name from `#[serde(rename = "...")]` if exists, otherwise from params name.
+fn construct_rpcname_from_attrs(attrs: &[syn::Attribute], params_name: &syn::Ident) -> syn::LitStr {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("serde")) {
+        if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+            for nested in list.nested.iter() {
+                if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = nested {
+                    if let (true, syn::Lit::Str(rename)) = (nv.path.is_ident("rename"), &nv.lit) {
+                        return rename.clone();
+                    }
+                }
+            }
+        }
+    }
+    construct_rpcname_from_params_name(params_name.to_string().as_ref())
+}
+
 #[proc_macro]
 pub fn construct_rpcname(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input: proc_macro2::TokenStream = input.into();
     let output = {
-        let params_name: syn::Ident = syn::parse2(input).unwrap();
-        let rpcname = construct_rpcname_from_params_name(params_name.to_string().as_ref());
+        let parser = |input: ParseStream| {
+            let attrs = input.call(syn::Attribute::parse_outer)?;
+            let params_name: syn::Ident = input.parse()?;
+            Ok(construct_rpcname_from_attrs(&attrs, &params_name))
+        };
+        let rpcname = parser.parse2(input).unwrap();
         quote!(#rpcname)
     };
     output.into()
 }
 
+// Get the predicate from `#[serde(skip_serializing_if = "...")]` if exists.
+fn construct_skip_serializing_if(attrs: &[syn::Attribute]) -> Option<syn::ExprPath> {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("serde")) {
+        if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+            for nested in list.nested.iter() {
+                if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = nested {
+                    if let (true, syn::Lit::Str(path)) =
+                        (nv.path.is_ident("skip_serializing_if"), &nv.lit)
+                    {
+                        return Some(path.parse().unwrap());
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
 fn generate_attrs_list(attrs_vec: &[syn::Attribute]) -> proc_macro2::TokenStream {
     let mut attrs = quote!();
     for attr in attrs_vec.iter() {
@@ -98,6 +252,7 @@ pub fn construct_params(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 
     let output = {
         let ParamsType {
+            attrs: params_attrs,
             name,
             colon_token: _colon_token,
             brace_token: _brace_token,
@@ -108,17 +263,48 @@ pub fn construct_params(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 
         let fields_size = fields.len();
         let mut ele_req = fields_size;
-        let rpcname = construct_rpcname_from_params_name(name.to_string().as_ref());
+        let rpcname = construct_rpcname_from_attrs(&params_attrs, &name);
+
+        let named_size = fields.iter().filter(|field| field.name.is_some()).count();
+        if named_size != 0 && named_size != fields_size {
+            panic!(
+                "The params of [{}] should be all named or all unnamed.",
+                rpcname.value()
+            );
+        }
+        let param_names = fields
+            .iter()
+            .filter_map(|field| field.name.as_ref())
+            .map(|name| syn::LitStr::new(&name.to_string(), name.span()));
 
         let mut types = quote!();
         let mut params_with_types = quote!();
         let mut params = quote!();
         let mut params_into_vec = quote!();
+        let mut params_from_raw_values = quote!();
+        // Only the trailing params are skipped, the others keep their positions.
+        let mut serialize_len = quote!();
+        let mut serialize_fields = quote!();
+
+        // Decode each param on its own, so the index of the failed one is known.
+        let decode_param = |attrs: &[syn::Attribute], index: usize| {
+            let missing = if attrs.is_empty() {
+                quote!(serde_json::from_str("null").map_err(|err| (#index, err))?)
+            } else {
+                quote!(Default::default())
+            };
+            quote!(match values.next().flatten() {
+                Some(value) => serde_json::from_str(value.get()).map_err(|err| (#index, err))?,
+                None => #missing,
+            })
+        };
+
+        let param_types = fields.iter().map(|field| &field.typ);
 
         match fields_size {
             0 => {}
             1 => {
-                let TypeWithAttrs { attrs, typ } = &fields.iter().next().unwrap();
+                let TypeWithAttrs { attrs, typ, .. } = &fields.iter().next().unwrap();
                 if !attrs.is_empty() {
                     ele_req = ele_req - 1;
                 }
@@ -127,11 +313,17 @@ pub fn construct_params(input: proc_macro::TokenStream) -> proc_macro::TokenStre
                 params_with_types = quote!(param: #typ);
                 params = quote!(param, OneItemTupleTrick::default());
                 let index = syn::Index::from(0);
-                params_into_vec = quote!(serde_json::to_value(self.#index).unwrap())
+                params_into_vec = quote!(serde_json::to_value(self.#index).unwrap());
+                let param = decode_param(attrs, 0);
+                params_from_raw_values = quote!(#param, OneItemTupleTrick::default());
+                if let Some(skip) = construct_skip_serializing_if(attrs) {
+                    serialize_len = quote!(if #skip(&self.#index) { len = 0; });
+                }
+                serialize_fields = quote!(if len > 0 { state.serialize_field(&self.#index)?; });
             }
             _ => {
                 let mut param_num = 0;
-                for TypeWithAttrs { attrs, typ } in fields.iter() {
+                for TypeWithAttrs { attrs, typ, .. } in fields.iter() {
                     let param_attrs = generate_attrs_list(attrs.as_slice());
                     if !attrs.is_empty() {
                         ele_req = ele_req - 1;
@@ -145,15 +337,43 @@ pub fn construct_params(input: proc_macro::TokenStream) -> proc_macro::TokenStre
                     params_into_vec = quote!(
                         #params_into_vec
                         serde_json::to_value(self.#index).unwrap(), );
+                    let param = decode_param(attrs, param_num);
+                    params_from_raw_values = quote!(#params_from_raw_values #param,);
+                    if let Some(skip) = construct_skip_serializing_if(attrs) {
+                        let after = param_num + 1;
+                        serialize_len = quote!(
+                            if len == #after && #skip(&self.#index) { len = #param_num; }
+                            #serialize_len
+                        );
+                    }
+                    serialize_fields = quote!(
+                        #serialize_fields
+                        if len > #param_num { state.serialize_field(&self.#index)?; }
+                    );
                     param_num += 1;
                 }
             }
         };
 
         quote!(
-            #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+            #[derive(Deserialize, Debug, Clone, PartialEq)]
             pub struct #name (#types);
 
+            impl serde::Serialize for #name {
+                #[allow(unused_mut)]
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    use serde::ser::SerializeTupleStruct;
+                    let mut len = #fields_size;
+                    #serialize_len
+                    let mut state = serializer.serialize_tuple_struct(stringify!(#name), len)?;
+                    #serialize_fields
+                    state.end()
+                }
+            }
+
             impl #name {
                 pub fn new(#params_with_types) -> #name {
                     #name(#params)
@@ -176,9 +396,156 @@ pub fn construct_params(input: proc_macro::TokenStream) -> proc_macro::TokenStre
                     #rpcname
                 }
 
+                fn param_names() -> &'static [&'static str] {
+                    &[#(#param_names),*]
+                }
+
                 fn value_vec(self) -> Vec<serde_json::Value> {
                     vec![#params_into_vec]
                 }
+
+                #[allow(unused_variables)]
+                fn param_schemas(components: &mut OpenRpcComponents) -> Vec<serde_json::Value> {
+                    vec![#(<#param_types as OpenRpcSchema>::schema(components)),*]
+                }
+
+                #[allow(unused_mut, unused_variables)]
+                fn from_raw_values(
+                    values: Vec<Option<&serde_json::value::RawValue>>,
+                ) -> Result<Self, (usize, serde_json::Error)> {
+                    let mut values = values.into_iter();
+                    Ok(#name(#params_from_raw_values))
+                }
+            }
+        )
+    };
+
+    proc_macro::TokenStream::from(output)
+}
+
+#[proc_macro]
+pub fn construct_handler(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let output = {
+        let HandlerInput { defaults, entries } = syn::parse2(input).unwrap();
+
+        let mut methods = quote!();
+        let mut arms = quote!();
+        for MethodEntry { enum_name, params } in entries.iter() {
+            let handler_name =
+                construct_handler_name_from_enum_name(enum_name.to_string().as_ref());
+            let params_name = &params.name;
+            let resp = &params.resp;
+            let body = match defaults.iter().find(|d| d.enum_name == *enum_name) {
+                Some(HandlerDefault { default, .. }) => quote!(#default(params)),
+                None => quote!({
+                    let _ = params;
+                    Err(Error::method_not_found())
+                }),
+            };
+            methods = quote!(
+                #methods
+
+                async fn #handler_name(&self, params: #params_name) -> Result<#resp, Error> {
+                    #body
+                }
+            );
+            arms = quote!(
+                #arms
+                Call::#enum_name { params } => self
+                    .#handler_name(params)
+                    .await
+                    .map(ResponseResult::#enum_name),
+            );
+        }
+
+        quote!(
+            /// Typed server side of all JSON-RPC methods.
+            ///
+            /// Every method defaults to `MethodNotFound`, so a backend only needs to
+            /// implement the methods it supports.
+            #[async_trait::async_trait]
+            pub trait EthRpcHandler: Send + Sync {
+                #methods
+
+                /// Route the request to its handler method and wrap the result into `Output`.
+                async fn dispatch(&self, req: Request) -> Output {
+                    let info = req.get_info();
+                    let result = match req.call {
+                        #arms
+                    };
+                    match result {
+                        Ok(result) => RpcSuccess::new(info).set_result(result).output(),
+                        Err(err) => Output::Failure(RpcFailure::from_options(info, err)),
+                    }
+                }
+            }
+        )
+    };
+
+    proc_macro::TokenStream::from(output)
+}
+
+#[proc_macro]
+pub fn construct_methods(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let output = {
+        let parser = Punctuated::<MethodEntry, Token![,]>::parse_terminated;
+        let entries = parser.parse2(input).unwrap();
+
+        let mut infos = quote!();
+        let mut schemas = quote!();
+        for MethodEntry { params, .. } in entries.iter() {
+            let rpcname = construct_rpcname_from_attrs(&params.attrs, &params.name);
+            let namespace = construct_namespace_from_rpcname(&rpcname.value());
+            let required_len = required_len(&params.fields);
+            let valid_len = params.fields.len();
+            let param_names = params
+                .fields
+                .iter()
+                .filter_map(|field| field.name.as_ref())
+                .map(|name| name.to_string());
+            let param_types = params.fields.iter().map(|field| {
+                let typ = &field.typ;
+                quote!(#typ).to_string().replace(' ', "")
+            });
+            let result_type = params.resp.to_string();
+            infos = quote!(
+                #infos
+                MethodInfo {
+                    name: #rpcname,
+                    namespace: #namespace,
+                    required_len: #required_len,
+                    valid_len: #valid_len,
+                    param_names: &[#(#param_names),*],
+                    param_types: &[#(#param_types),*],
+                    result_type: #result_type,
+                },
+            );
+            let params_name = &params.name;
+            schemas = quote!(
+                #schemas
+                (
+                    <#params_name as JsonRpcRequest>::param_schemas(components),
+                    <<#params_name as JsonRpcRequest>::Response as OpenRpcSchema>::schema(
+                        components,
+                    ),
+                ),
+            );
+        }
+
+        quote!(
+            /// All JSON-RPC methods, in the order of `impl_for_each_jsonrpc_requests`.
+            pub static METHODS: &[MethodInfo] = &[#infos];
+
+            /// The schemas of the params and the result of all methods, in the order
+            /// of `METHODS`.
+            pub(crate) fn method_schemas(
+                components: &mut OpenRpcComponents,
+            ) -> Vec<(Vec<serde_json::Value>, serde_json::Value)> {
+                vec![#schemas]
             }
         )
     };
@@ -178,6 +178,27 @@ impl Error {
         }
     }
 
+    /// Creates new `InvalidRequest` with the reason
+    pub fn invalid_request_with_message<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Error {
+            code: ErrorCode::InvalidRequest,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Creates new `InvalidRequest` for a batch larger than allowed
+    pub fn batch_too_large(max_batch_size: usize) -> Self {
+        Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Batch size exceeds the limit of {}", max_batch_size),
+            data: None,
+        }
+    }
+
     pub fn server_error<M>(err_code: i64, message: M) -> Self
     where
         M: Into<String>,
@@ -189,6 +210,48 @@ impl Error {
         }
     }
 
+    /// Creates new `ServerError` for an unknown or expired filter id
+    pub fn filter_not_found() -> Self {
+        Error::server_error(-32_000, "filter not found")
+    }
+
+    /// Creates new `ServerError` for an account not in the signer
+    pub fn unknown_account() -> Self {
+        Error::server_error(-32_000, "unknown account")
+    }
+
+    /// Creates new `ServerError` for a locked account signing without password
+    pub fn account_locked() -> Self {
+        Error::server_error(-32_000, "authentication needed: password or unlock")
+    }
+
+    /// Creates new `ServerError` for a failure of the signer or the keystore
+    pub fn signer_error<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Error::server_error(-32_000, message)
+    }
+
+    /// Creates new `ServerError` for a state proof which can not be decoded
+    pub fn invalid_state_proof<M>(message: M) -> Self
+    where
+        M: std::fmt::Display,
+    {
+        Error::server_error(-32_000, format!("invalid state proof: {}", message))
+    }
+
+    /// Creates new `ServerError` for a transaction of a block without receipt
+    pub fn receipt_not_found<H>(transaction_hash: H) -> Self
+    where
+        H: std::fmt::Debug,
+    {
+        Error::server_error(
+            -32_000,
+            format!("receipt of transaction {:?} not found", transaction_hash),
+        )
+    }
+
     pub fn parse_error_with_message<M>(message: M) -> Self
     where
         M: Into<String>,
@@ -209,6 +272,48 @@ impl Error {
         }
     }
 
+    /// Creates new `InvalidParams` with the expected and received params length
+    pub fn invalid_params_len_of(
+        method: &str,
+        required_len: usize,
+        valid_len: usize,
+        received_len: usize,
+    ) -> Self {
+        Error {
+            code: ErrorCode::InvalidParams,
+            message: "Invalid JSON-RPC params length".to_owned(),
+            data: Some(serde_json::json!({
+                "method": method,
+                "expected": {
+                    "min": required_len,
+                    "max": valid_len,
+                },
+                "received": received_len,
+            })),
+        }
+    }
+
+    /// Creates new `InvalidParams` for the param which failed to decode
+    pub fn invalid_param<M>(method: &str, index: usize, name: Option<&str>, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        let message = message.into();
+        Error {
+            code: ErrorCode::InvalidParams,
+            message: match name {
+                Some(name) => format!("Invalid param `{}`: {}", name, message),
+                None => format!("Invalid param at index {}: {}", index, message),
+            },
+            data: Some(serde_json::json!({
+                "method": method,
+                "index": index,
+                "name": name,
+                "error": message,
+            })),
+        }
+    }
+
     pub fn query_error() -> Self {
         Self::new(ErrorCode::QueryError)
     }
@@ -14,11 +14,15 @@
 
 #[macro_use]
 extern crate serde_derive;
+#[cfg(test)]
+#[macro_use]
+extern crate serde_json;
 #[macro_use]
 mod macros;
 
 mod error;
 pub mod rpc_complete;
+pub mod rpc_handler;
 pub mod rpc_request;
 pub mod rpc_response;
 pub mod rpc_types;
@@ -12,15 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::internals::construct_rpcname;
+use crate::rpc_complete::policy::MethodPolicy;
 use crate::rpc_request::{
+    debug_traceBlockByNumberParams, debug_traceCallParams, debug_traceTransactionParams,
     eth_accountsParams, eth_blockNumberParams, eth_callParams, eth_chainIdParams,
-    eth_estimateGasParams, eth_gasPriceParams, eth_getBalanceParams, eth_getBlockByHashParams,
-    eth_getBlockByNumberParams, eth_getBlockTransactionCountByHashParams,
-    eth_getBlockTransactionCountByNumberParams, eth_getCodeParams, eth_getLogsParams,
-    eth_getStorageAtParams, eth_getTransactionByBlockHashAndIndexParams,
-    eth_getTransactionByBlockNumberAndIndexParams, eth_getTransactionByHashParams,
-    eth_getTransactionCountParams, eth_getTransactionReceiptParams, eth_maxPriorityFeePerGasParams,
-    eth_sendRawTransactionParams, eth_sendTransactionParams, eth_syncingParams, net_versionParams,
+    eth_createAccessListParams, eth_estimateGasParams, eth_feeHistoryParams, eth_gasPriceParams,
+    eth_getBalanceParams, eth_getBlockByHashParams, eth_getBlockByNumberParams,
+    eth_getBlockReceiptsParams, eth_getBlockTransactionCountByHashParams,
+    eth_getBlockTransactionCountByNumberParams, eth_getCodeParams, eth_getFilterChangesParams,
+    eth_getFilterLogsParams, eth_getLogsParams, eth_getProofParams, eth_getStorageAtParams,
+    eth_getTransactionByBlockHashAndIndexParams, eth_getTransactionByBlockNumberAndIndexParams,
+    eth_getTransactionByHashParams, eth_getTransactionCountParams, eth_getTransactionReceiptParams,
+    eth_getUncleByBlockHashAndIndexParams, eth_getUncleByBlockNumberAndIndexParams,
+    eth_getUncleCountByBlockHashParams, eth_getUncleCountByBlockNumberParams,
+    eth_maxPriorityFeePerGasParams, eth_newBlockFilterParams, eth_newFilterParams,
+    eth_newPendingTransactionFilterParams, eth_sendRawTransactionParams, eth_sendTransactionParams,
+    eth_signParams, eth_signTransactionParams, eth_signTypedData_v4Params, eth_subscribeParams,
+    eth_syncingParams, eth_uninstallFilterParams, eth_unsubscribeParams, net_listeningParams,
+    net_peerCountParams, net_versionParams, personal_signParams, rpc_discoverParams,
+    rpc_modulesParams, trace_blockParams, trace_callParams, trace_filterParams,
+    trace_replayTransactionParams, trace_transactionParams, txpool_contentParams,
+    txpool_inspectParams, txpool_statusParams, web3_clientVersionParams, web3_sha3Params,
     BlockNumberParams, CallParams, EstimateQuotaParams, GetAbiParams, GetBalanceParams,
     GetBlockByHashParams, GetBlockByNumberParams, GetBlockHeaderParams, GetCensoredAddrsParams,
     GetCodeParams, GetFilterChangesParams, GetFilterLogsParams, GetLogsParams, GetMetaDataParams,
@@ -30,9 +43,12 @@ use crate::rpc_request::{
     PeerCountParams, PeersInfoParams, SendRawTransactionParams, SendTransactionParams,
     UninstallFilterParams,
 };
-use crate::rpc_request::{Call, JsonRpcRequest, PartialCall, PartialRequest, Request};
-use crate::{impl_for_each_jsonrpc_requests, rpc_types::Params as PartialParams, Error};
-use serde_json;
+use crate::rpc_request::{
+    Call, JsonRpcRequest, MethodInfo, PartialCall, PartialRequest, RawRequest, Request,
+};
+use crate::{impl_for_each_jsonrpc_requests, Error};
+use serde_json::{self, value::RawValue};
+use std::collections::BTreeMap;
 
 pub trait Complete {
     type Output;
@@ -46,65 +62,235 @@ impl Complete for PartialRequest {
     type Error = Error;
 
     fn complete(self) -> Result<Self::Output, Self::Error> {
-        let PartialRequest { jsonrpc, id, call } = self;
-        if let Some(part_call) = call {
-            part_call
+        let PartialRequest {
+            jsonrpc,
+            id,
+            call,
+            method,
+        } = self;
+        match (call, method.name) {
+            (Some(part_call), _) => part_call
                 .complete()
-                .map(|full_call| Request::new(jsonrpc, id, full_call))
-        } else {
-            Err(Error::method_not_found())
+                .map(|full_call| Request::new(jsonrpc, id, full_call)),
+            (None, Some(_)) => Err(Error::method_not_found()),
+            // A request object without `method` is not a valid request.
+            (None, None) => Err(Error::invalid_request()),
+        }
+    }
+}
+
+impl PartialRequest {
+    /// Check the method against the policy before the params are decoded,
+    /// then complete the request.
+    pub fn complete_with_policy(self, policy: &MethodPolicy) -> Result<Request, Error> {
+        if let Some(ref part_call) = self.call {
+            policy.check(part_call.get_method())?;
         }
+        self.complete()
     }
 }
 
+impl<'a> Complete for RawRequest<'a> {
+    type Output = Request;
+    type Error = Error;
+
+    fn complete(self) -> Result<Self::Output, Self::Error> {
+        let RawRequest {
+            jsonrpc,
+            id,
+            method,
+            params,
+        } = self;
+        match method {
+            Some(method) => raw_call_complete(&method, params)
+                .map(|full_call| Request::new(jsonrpc, id.unwrap_or_default(), full_call)),
+            // A request object without `method` is not a valid request.
+            None => Err(Error::invalid_request()),
+        }
+    }
+}
+
+impl<'a> RawRequest<'a> {
+    /// Check the method against the policy before the params are decoded,
+    /// then complete the request.
+    pub fn complete_with_policy(self, policy: &MethodPolicy) -> Result<Request, Error> {
+        if let Some(ref method) = self.method {
+            if MethodInfo::find(method).is_some() {
+                policy.check(method)?;
+            }
+        }
+        self.complete()
+    }
+}
+
+// Map the named params onto the positional params. The optional params
+// omitted before the last given one are left as `None`.
+fn named_params_into_array<'a>(
+    mut map: BTreeMap<String, &'a RawValue>,
+    names: &[&str],
+    required_len: usize,
+) -> Result<Vec<Option<&'a RawValue>>, Error> {
+    if names.is_empty() {
+        return Err(Error::invalid_params("named params are not supported"));
+    }
+    let mut values = Vec::with_capacity(names.len());
+    for (index, name) in names.iter().enumerate() {
+        match map.remove(*name) {
+            Some(value) => values.push(Some(value)),
+            None if index < required_len => {
+                return Err(Error::invalid_params(format!("missing param `{}`", name)))
+            }
+            None => values.push(None),
+        }
+    }
+    if let Some(name) = map.keys().next() {
+        return Err(Error::invalid_params(format!("unknown param `{}`", name)));
+    }
+    while let Some(None) = values.last() {
+        values.pop();
+    }
+    Ok(values)
+}
+
+// Split the raw params into raw values, the array or object is told by the
+// first non-whitespace byte.
+fn raw_params_into_array<P>(params: Option<&RawValue>) -> Result<Vec<Option<&RawValue>>, Error>
+where
+    P: JsonRpcRequest,
+{
+    let params = match params {
+        Some(params) => params.get(),
+        None if P::required_len() == 0 => return Ok(Vec::new()),
+        None => return Err(Error::invalid_params("params is requeired")),
+    };
+    let invalid = |err: serde_json::Error| Error::invalid_params(error_message(&err));
+    match params.bytes().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'[') => serde_json::from_str::<Vec<&RawValue>>(params)
+            .map(|values| values.into_iter().map(Some).collect())
+            .map_err(invalid),
+        Some(b'{') => named_params_into_array(
+            serde_json::from_str(params).map_err(invalid)?,
+            P::param_names(),
+            P::required_len(),
+        ),
+        _ => Err(Error::invalid_params(
+            "params should be an array or an object",
+        )),
+    }
+}
+
+fn check_params_len<P>(rpcname: &str, len: usize) -> Result<(), Error>
+where
+    P: JsonRpcRequest,
+{
+    if len < P::required_len() || len > P::valid_len() {
+        return Err(Error::invalid_params_len_of(
+            rpcname,
+            P::required_len(),
+            P::valid_len(),
+            len,
+        ));
+    }
+    Ok(())
+}
+
+// The message without the position, which is only known when decoded from
+// the raw JSON, so both paths give the same message.
+fn error_message(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    let position = format!(" at line {} column {}", err.line(), err.column());
+    match message.strip_suffix(&position) {
+        Some(message) => message.to_owned(),
+        None => message,
+    }
+}
+
+fn decode_error<P>(rpcname: &str, index: usize, err: serde_json::Error) -> Error
+where
+    P: JsonRpcRequest,
+{
+    let name = P::param_names().get(index).copied();
+    Error::invalid_param(rpcname, index, name, error_message(&err))
+}
+
+// Check the params length, then decode the params one by one straight from
+// the raw JSON.
+fn decode_raw_params<P>(rpcname: &str, params: Option<&RawValue>) -> Result<P, Error>
+where
+    P: JsonRpcRequest,
+{
+    let values = raw_params_into_array::<P>(params)?;
+    check_params_len::<P>(rpcname, values.len())?;
+    P::from_raw_values(values).map_err(|(index, err)| decode_error::<P>(rpcname, index, err))
+}
+
 macro_rules! partial_call_complete {
-    ($( ($enum_name:ident, $params_name:ident: $params_list:expr, $result_type:ident) ),+ ,) => {
-        partial_call_complete!($( ($enum_name, $params_name) ),+);
+    ($( ($(#[$attr:meta])* $enum_name:ident, $params_name:ident: $params_list:tt, $result_type:ident) ),+ ,) => {
+        partial_call_complete!($( ($(#[$attr])* $enum_name, $params_name) ),+);
     };
-    ($( ($enum_name:ident, $params_name:ident) ),+) => {
+    ($( ($(#[$attr:meta])* $enum_name:ident, $params_name:ident) ),+) => {
+        impl PartialCall {
+            pub fn get_method(&self) -> &'static str {
+                match self {
+                    $(
+                        PartialCall::$enum_name { .. } => construct_rpcname!($(#[$attr])* $params_name),
+                    )+
+                }
+            }
+        }
+
         impl Complete for PartialCall {
             type Output = Call;
             type Error = Error;
 
+            // The params are decoded the same way as `RawRequest`'s.
             fn complete(self) -> Result<Self::Output, Self::Error> {
-                match self {
+                let method = self.get_method();
+                let params = match self {
                     $(
-                        PartialCall::$enum_name { params } => {
-                            if let Some(params) = params {
-                                let pparams: PartialParams = serde_json::from_value(params.clone())?;
-                                if pparams.len() < $params_name::required_len()
-                                    && pparams.len() > $params_name::valid_len() {
-                                    Err(Error::invalid_params_len())
-                                } else {
-                                    Ok(Call::$enum_name{ params: serde_json::from_value(params)? })
-                                }
-                            } else {
-                                if $params_name::required_len() == 0 {
-                                    Ok(Call::$enum_name{
-                                        params: serde_json::from_value(
-                                                    serde_json::Value::Array(Vec::new()))?})
-                                } else {
-                                    Err(Error::invalid_params("params is requeired"))
-                                }
-                            }
-                        },
+                        PartialCall::$enum_name { params } => params,
                     )+
-                }
+                };
+                let params = params
+                    .map(|params| serde_json::value::to_raw_value(&params))
+                    .transpose()
+                    .map_err(|err| Error::invalid_params(error_message(&err)))?;
+                raw_call_complete(method, params.as_deref())
             }
         }
+
+        // The decoders in the order of `METHODS`.
+        static RAW_CALL_DECODERS: &[RawCallDecoder] = &[
+            $(
+                |method, params| {
+                    decode_raw_params::<$params_name>(method, params)
+                        .map(|params| Call::$enum_name { params })
+                },
+            )+
+        ];
     }
 }
 
+type RawCallDecoder = fn(&str, Option<&RawValue>) -> Result<Call, Error>;
+
+fn raw_call_complete(method: &str, params: Option<&RawValue>) -> Result<Call, Error> {
+    let index = MethodInfo::index(method).ok_or_else(Error::method_not_found)?;
+    RAW_CALL_DECODERS[index](method, params)
+}
+
 impl_for_each_jsonrpc_requests!(partial_call_complete);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ethereum_types::H256;
+    use crate::rpc_types::ethereum_types::EthCallRequest;
+    use crate::rpc_types::BlockNumber;
+    use crate::ErrorCode;
+    use ethereum_types::{H160, H256};
 
     #[test]
     fn test_get_transaction_receipt_params_complete() {
-        let params = GetTransactionReceiptParams::new(H256::from(10).into());
+        let params = GetTransactionReceiptParams::new(H256::from_low_u64_be(10).into());
         let full_req = params.into_request(1);
 
         let req_str = r#"{
@@ -140,8 +326,231 @@ mod tests {
         let part_req = serde_json::from_str::<PartialRequest>(&req_str).unwrap();
         assert_eq!(
             part_req.complete().err().unwrap(),
-            Error::invalid_params_len()
+            Error::invalid_params_len_of("getTransactionReceipt", 1, 1, 2)
+        );
+    }
+
+    #[test]
+    fn test_invalid_params_complete_error() {
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBalance",
+            "params": ["0x000000000000000000000000000000000000000a"]
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        let err = part_req.complete().err().unwrap();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        assert_eq!(
+            err.data,
+            Some(json!({
+                "method": "eth_getBalance",
+                "expected": {"min": 2, "max": 2},
+                "received": 1,
+            }))
+        );
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBalance",
+            "params": ["0x0a", "latest"]
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        let err = part_req.complete().err().unwrap();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        assert_eq!(
+            err.message,
+            "Invalid param `address`: invalid format: [0x0a]"
+        );
+        assert_eq!(
+            err.data,
+            Some(json!({
+                "method": "eth_getBalance",
+                "index": 0,
+                "name": "address",
+                "error": "invalid format: [0x0a]",
+            }))
+        );
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getTransaction",
+            "params": ["0x000000000000000000000000000000000000000000000000000000000000000a", 1]
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        let err = part_req.complete().err().unwrap();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        assert_eq!(err.data.unwrap()["index"], json!(1));
+    }
+
+    #[test]
+    fn test_named_params_complete() {
+        let params =
+            eth_getBalanceParams::new(H160::from_low_u64_be(10).into(), BlockNumber::latest());
+        let full_req = params.into_request(1);
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBalance",
+            "params": {
+                "block": "latest",
+                "address": "0x000000000000000000000000000000000000000a"
+            }
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(part_req.complete().unwrap(), full_req);
+
+        let params = eth_estimateGasParams::new(EthCallRequest::default(), BlockNumber::latest());
+        let full_req = params.into_request(2);
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "eth_estimateGas",
+            "params": {"transaction": {}}
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(part_req.complete().unwrap(), full_req);
+
+        // `stateOverride` is omitted before `blockOverrides`.
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "eth_call",
+            "params": {
+                "transaction": {},
+                "block": "latest",
+                "blockOverrides": {"number": "0x1"}
+            }
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        let params: eth_callParams = part_req.complete().unwrap().call.into();
+        assert_eq!(params.2, None);
+        assert!(params.3.is_some());
+        let raw_req = serde_json::from_str::<RawRequest>(req_str).unwrap();
+        assert_eq!(raw_req.complete().unwrap().call, params.into());
+    }
+
+    #[test]
+    fn test_named_params_complete_error() {
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBalance",
+            "params": {"address": "0x000000000000000000000000000000000000000a"}
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(
+            part_req.complete().err().unwrap(),
+            Error::invalid_params("missing param `block`")
+        );
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBalance",
+            "params": {
+                "address": "0x000000000000000000000000000000000000000a",
+                "block": "latest",
+                "tag": "latest"
+            }
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(
+            part_req.complete().err().unwrap(),
+            Error::invalid_params("unknown param `tag`")
         );
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getBalance",
+            "params": {"address": "0x000000000000000000000000000000000000000a"}
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(
+            part_req.complete().err().unwrap(),
+            Error::invalid_params("named params are not supported")
+        );
+    }
+
+    #[test]
+    fn test_complete_with_policy() {
+        let policy = MethodPolicy::allow_all().deny_method("opCensoredAddress");
+
+        // Denied before the params are decoded.
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "opCensoredAddress",
+            "params": ["invalid"]
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(
+            part_req.call.as_ref().unwrap().get_method(),
+            "opCensoredAddress"
+        );
+        assert_eq!(
+            part_req.complete_with_policy(&policy).err().unwrap(),
+            Error::method_not_found()
+        );
+
+        let req_str = r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "rpc.discover"
+        }"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        assert_eq!(part_req.call.as_ref().unwrap().get_method(), "rpc.discover");
+        assert!(part_req.complete_with_policy(&policy).is_ok());
+    }
+
+    #[test]
+    fn test_raw_request_complete() {
+        let req_strs = [
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": [{"to": "0x0000000000000000000000000000000000000001", "data": "0x70a08231"}, "latest"]}"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "eth_getLogs", "params": [{"fromBlock": "0x1", "toBlock": "latest", "topics": []}]}"#,
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "eth_getBalance", "params": {"address": "0x0000000000000000000000000000000000000001", "block": "latest"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 4, "method": "eth_blockNumber", "params": null}"#,
+            r#"{"jsonrpc": "2.0", "id": 4, "method": "eth_call", "params": [{"to": "0x0000000000000000000000000000000000000001"}, "latest", null, {"number": "0x1"}]}"#,
+            r#"{"jsonrpc": "2.0", "id": 5, "method": "eth_getBalance", "params": ["0x01"]}"#,
+            r#"{"jsonrpc": "2.0", "id": 6, "method": "eth_getBalance", "params": {"addr": "0x01"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 7, "method": "eth_getBalance", "params": 1}"#,
+            r#"{"jsonrpc": "2.0", "id": 8, "method": "eth_getBalance"}"#,
+            r#"{"jsonrpc": "2.0", "id": 8, "method": "eth_getBalance", "params": ["0x01", "latest"]}"#,
+            r#"{"jsonrpc": "2.0", "id": 8, "method": "eth_getLogs", "params": [{"fromBlock": 1}]}"#,
+            r#"{"jsonrpc": "2.0", "id": 9, "method": "eth_foo", "params": []}"#,
+        ];
+        for (index, req_str) in req_strs.iter().enumerate() {
+            let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+            let raw_req = serde_json::from_str::<RawRequest>(req_str).unwrap();
+            let full_req = raw_req.complete();
+            assert_eq!(full_req.is_ok(), index < 5, "{}", req_str);
+            assert_eq!(full_req, part_req.complete(), "{}", req_str);
+        }
+    }
+
+    #[test]
+    fn test_raw_call_decoders() {
+        use crate::rpc_request::METHODS;
+
+        assert_eq!(RAW_CALL_DECODERS.len(), METHODS.len());
+        for info in METHODS.iter().filter(|info| info.required_len == 0) {
+            let call = raw_call_complete(info.name, None).unwrap();
+            assert_eq!(call.get_method(), info.name);
+        }
+    }
+
+    #[test]
+    fn test_raw_request_without_method() {
+        let req_str = r#"{"jsonrpc": "2.0", "id": 4, "foo": "boo"}"#;
+        let part_req = serde_json::from_str::<PartialRequest>(req_str).unwrap();
+        let raw_req = serde_json::from_str::<RawRequest>(req_str).unwrap();
+        assert_eq!(raw_req.complete().err().unwrap(), Error::invalid_request());
+        assert_eq!(part_req.complete().err().unwrap(), Error::invalid_request());
     }
 
     #[test]
@@ -152,7 +561,7 @@ mod tests {
         let req_str = r#"{
             "jsonrpc": "2.0",
             "id": 2,
-            "method": "blockNumber"
+            "method": "eth_blockNumber"
         }"#;
         let part_req = serde_json::from_str::<PartialRequest>(&req_str).unwrap();
         assert_eq!(part_req.complete().unwrap(), full_req);
@@ -167,10 +576,7 @@ mod tests {
         }"#;
         let part_req = serde_json::from_str::<PartialRequest>(&req_str).unwrap();
 
-        assert_eq!(
-            part_req.complete().err().unwrap(),
-            Error::method_not_found()
-        );
+        assert_eq!(part_req.complete().err().unwrap(), Error::invalid_request());
 
         let req_str = r#"{
             "jsonrpc": "2.0",
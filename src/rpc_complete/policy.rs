@@ -0,0 +1,151 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use crate::rpc_request::MethodInfo;
+use crate::Error;
+
+/// Allow or deny methods by namespace or by exact method name.
+///
+/// The exact method rules take precedence over the namespace rules, and deny
+/// takes precedence over allow at the same level. The methods not matched by any
+/// rule follow the default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodPolicy {
+    default_allow: bool,
+    allowed_namespaces: HashSet<String>,
+    denied_namespaces: HashSet<String>,
+    allowed_methods: HashSet<String>,
+    denied_methods: HashSet<String>,
+    error: Error,
+}
+
+impl MethodPolicy {
+    fn new(default_allow: bool) -> Self {
+        MethodPolicy {
+            default_allow,
+            allowed_namespaces: HashSet::new(),
+            denied_namespaces: HashSet::new(),
+            allowed_methods: HashSet::new(),
+            denied_methods: HashSet::new(),
+            error: Error::method_not_found(),
+        }
+    }
+
+    /// All methods are allowed unless denied.
+    pub fn allow_all() -> Self {
+        Self::new(true)
+    }
+
+    /// All methods are denied unless allowed.
+    pub fn deny_all() -> Self {
+        Self::new(false)
+    }
+
+    pub fn allow_namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.allowed_namespaces.insert(namespace.into());
+        self
+    }
+
+    pub fn deny_namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.denied_namespaces.insert(namespace.into());
+        self
+    }
+
+    pub fn allow_method<S: Into<String>>(mut self, method: S) -> Self {
+        self.allowed_methods.insert(method.into());
+        self
+    }
+
+    pub fn deny_method<S: Into<String>>(mut self, method: S) -> Self {
+        self.denied_methods.insert(method.into());
+        self
+    }
+
+    /// Set the error returned for denied methods, `MethodNotFound` by default.
+    pub fn set_error(mut self, error: Error) -> Self {
+        self.error = error;
+        self
+    }
+
+    pub fn is_allowed(&self, method: &str) -> bool {
+        if self.denied_methods.contains(method) {
+            return false;
+        }
+        if self.allowed_methods.contains(method) {
+            return true;
+        }
+        let namespace = MethodInfo::find(method)
+            .map(|info| info.namespace)
+            .unwrap_or("cita");
+        if self.denied_namespaces.contains(namespace) {
+            return false;
+        }
+        if self.allowed_namespaces.contains(namespace) {
+            return true;
+        }
+        self.default_allow
+    }
+
+    pub fn check(&self, method: &str) -> Result<(), Error> {
+        if self.is_allowed(method) {
+            Ok(())
+        } else {
+            Err(self.error.clone())
+        }
+    }
+}
+
+impl Default for MethodPolicy {
+    fn default() -> Self {
+        MethodPolicy::allow_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MethodPolicy;
+    use crate::Error;
+
+    #[test]
+    fn test_allow_all_with_deny() {
+        let policy = MethodPolicy::allow_all()
+            .deny_namespace("cita")
+            .allow_method("getBlockByNumber");
+        assert!(policy.is_allowed("eth_getBalance"));
+        assert!(policy.is_allowed("net_version"));
+        assert!(policy.is_allowed("getBlockByNumber"));
+        assert!(!policy.is_allowed("opCensoredAddress"));
+        assert_eq!(
+            policy.check("opCensoredAddress"),
+            Err(Error::method_not_found())
+        );
+    }
+
+    #[test]
+    fn test_deny_all_with_allow() {
+        let policy = MethodPolicy::deny_all()
+            .allow_namespace("eth")
+            .deny_method("eth_sendTransaction")
+            .set_error(Error::server_error(-32_000, "method is disabled"));
+        assert!(policy.is_allowed("eth_call"));
+        assert!(!policy.is_allowed("eth_sendTransaction"));
+        assert!(!policy.is_allowed("net_version"));
+        assert_eq!(
+            policy.check("net_version"),
+            Err(Error::server_error(-32_000, "method is disabled"))
+        );
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use futures::stream::{self, StreamExt};
+use serde_json::{self, value::RawValue};
+
+use crate::rpc_complete::policy::MethodPolicy;
+use crate::rpc_handler::EthRpcHandler;
+use crate::rpc_request::{PartialRequest, RawRequest, RawRpcRequest, RequestInfo, ValidationMode};
+use crate::rpc_response::{Output, RpcFailure, RpcResponse};
+use crate::rpc_types::{Id, Version};
+use crate::{Error, ErrorCode};
+
+/// Limits applied while processing a batch request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchConfig {
+    /// The maximum number of entries accepted in one batch.
+    pub max_batch_size: usize,
+    /// The maximum number of entries executed at the same time.
+    pub concurrency: usize,
+    /// The methods allowed to call.
+    pub policy: MethodPolicy,
+    /// How strictly the request objects are checked.
+    pub validation: ValidationMode,
+}
+
+impl BatchConfig {
+    pub fn new(max_batch_size: usize, concurrency: usize) -> Self {
+        BatchConfig {
+            max_batch_size,
+            concurrency,
+            policy: MethodPolicy::default(),
+            validation: ValidationMode::default(),
+        }
+    }
+
+    pub fn set_policy(mut self, policy: MethodPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn set_validation(mut self, validation: ValidationMode) -> Self {
+        self.validation = validation;
+        self
+    }
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig::new(100, 16)
+    }
+}
+
+/// Complete and dispatch one request, if the method is allowed by the policy.
+pub async fn process_request<H>(handler: &H, req: PartialRequest, policy: &MethodPolicy) -> Output
+where
+    H: EthRpcHandler + ?Sized,
+{
+    let info = req.get_info();
+    match req.complete_with_policy(policy) {
+        Ok(req) => handler.dispatch(req).await,
+        Err(err) => Output::Failure(RpcFailure::from_options(info, err)),
+    }
+}
+
+/// Process a raw JSON-RPC body, which is either a single request or a batch.
+///
+/// The body is parsed in one pass, and every element of a batch is parsed on its
+/// own, so a malformed element only produces an `InvalidRequest` output for
+/// itself. Outputs of notifications are dropped, and `None` is returned when
+/// there is nothing to respond.
+pub async fn process_batch<H>(handler: &H, body: &str, config: &BatchConfig) -> Option<RpcResponse>
+where
+    H: EthRpcHandler + ?Sized,
+{
+    let entries = match RawRpcRequest::parse(body, config.validation) {
+        Ok(RawRpcRequest::Single(req)) => {
+            return process_raw_request(handler, req, &config.policy)
+                .await
+                .map(|output| RpcResponse::Single(Box::new(output)));
+        }
+        Ok(RawRpcRequest::Batch(entries)) => entries,
+        Err(err) => {
+            return Some(RpcResponse::Single(Box::new(failure(body, err))));
+        }
+    };
+
+    if entries.is_empty() {
+        return Some(RpcResponse::Single(Box::new(Output::invalid_request(
+            RequestInfo::default(),
+        ))));
+    }
+    if entries.len() > config.max_batch_size {
+        let err = Error::batch_too_large(config.max_batch_size);
+        return Some(RpcResponse::Single(Box::new(Output::Failure(
+            RpcFailure::from_options(RequestInfo::default(), err),
+        ))));
+    }
+
+    let outputs: Vec<Output> = stream::iter(entries)
+        .map(|entry| process_entry(handler, entry, config))
+        .buffered(config.concurrency.max(1))
+        .filter_map(|output| async move { output })
+        .collect()
+        .await;
+
+    if outputs.is_empty() {
+        None
+    } else {
+        Some(RpcResponse::Batch(outputs))
+    }
+}
+
+async fn process_entry<H>(handler: &H, entry: &RawValue, config: &BatchConfig) -> Option<Output>
+where
+    H: EthRpcHandler + ?Sized,
+{
+    match config.validation.parse_request(entry.get()) {
+        Ok(req) => process_raw_request(handler, req, &config.policy).await,
+        Err(err) => Some(failure(entry.get(), err)),
+    }
+}
+
+// Parse errors have no request info, for other errors the info is recovered
+// from the malformed entry. The version is `2.0` if it is unknown.
+fn failure(entry: &str, err: Error) -> Output {
+    if err.code == ErrorCode::ParseError {
+        Output::Failure(RpcFailure::from_options(RequestInfo::default(), err))
+    } else {
+        Output::Failure(RpcFailure::from_options(recover_info(entry), err))
+    }
+}
+
+// Returns `None` for notifications.
+async fn process_raw_request<H>(
+    handler: &H,
+    req: RawRequest<'_>,
+    policy: &MethodPolicy,
+) -> Option<Output>
+where
+    H: EthRpcHandler + ?Sized,
+{
+    let info = req.get_info();
+    let is_notification = req.is_notification();
+    let output = match req.complete_with_policy(policy) {
+        Ok(req) => handler.dispatch(req).await,
+        Err(err) => Output::Failure(RpcFailure::from_options(info, err)),
+    };
+    if is_notification {
+        None
+    } else {
+        Some(output)
+    }
+}
+
+// Recover as much of the request info as possible from a malformed entry.
+fn recover_info(entry: &str) -> RequestInfo {
+    let entry: HashMap<String, &RawValue> = serde_json::from_str(entry).unwrap_or_default();
+    let jsonrpc = entry
+        .get("jsonrpc")
+        .and_then(|v| serde_json::from_str::<Version>(v.get()).ok())
+        .or_else(|| Some(Version::default()));
+    let id = entry
+        .get("id")
+        .and_then(|v| serde_json::from_str::<Id>(v.get()).ok())
+        .unwrap_or_default();
+    RequestInfo::new(jsonrpc, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_request::eth_blockNumberParams;
+    use crate::rpc_types::Quantity;
+    use futures::executor::block_on;
+    use serde_json::Value;
+
+    struct BlockNumberOnly;
+
+    #[async_trait::async_trait]
+    impl EthRpcHandler for BlockNumberOnly {
+        async fn eth_block_number(
+            &self,
+            _params: eth_blockNumberParams,
+        ) -> Result<Quantity, Error> {
+            Ok(Quantity::from(16u64))
+        }
+    }
+
+    fn process(body: &str, config: &BatchConfig) -> Option<Value> {
+        block_on(process_batch(&BlockNumberOnly, body, config))
+            .map(|resp| serde_json::to_value(resp).unwrap())
+    }
+
+    #[test]
+    fn test_batch_isolates_malformed_entries() {
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_getBalance", "params": 1},
+            {"jsonrpc": "2.0", "id": 3, "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "id": 4, "foo": "boo"},
+            1
+        ]"#;
+        let resp = process(body, &BatchConfig::default()).unwrap();
+        let outputs = resp.as_array().unwrap();
+        assert_eq!(outputs.len(), 5);
+        assert_eq!(
+            outputs[0],
+            json!({"jsonrpc": "2.0", "id": 1, "result": "0x10"})
+        );
+        assert_eq!(outputs[1]["id"], json!(2));
+        assert!(outputs[1].get("error").is_some());
+        assert_eq!(outputs[2]["id"], json!(3));
+        assert_eq!(outputs[2]["error"]["code"], json!(-32_601));
+        assert_eq!(outputs[3]["id"], json!(4));
+        assert_eq!(outputs[3]["error"]["code"], json!(-32_600));
+        assert_eq!(outputs[4]["id"], Value::Null);
+        assert_eq!(outputs[4]["error"]["code"], json!(-32_600));
+    }
+
+    #[test]
+    fn test_batch_drops_notifications() {
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": null, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 7, "method": "eth_blockNumber"}
+        ]"#;
+        let resp = process(body, &BatchConfig::default()).unwrap();
+        assert_eq!(resp, json!([{"jsonrpc": "2.0", "id": 7, "result": "0x10"}]));
+
+        let body = r#"[{"jsonrpc": "2.0", "id": null, "method": "eth_blockNumber"}]"#;
+        assert_eq!(process(body, &BatchConfig::default()), None);
+    }
+
+    #[test]
+    fn test_batch_empty_and_too_large() {
+        let resp = process("[]", &BatchConfig::default()).unwrap();
+        assert_eq!(resp["id"], Value::Null);
+        assert_eq!(resp["error"]["code"], json!(-32_600));
+
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber"}
+        ]"#;
+        let resp = process(body, &BatchConfig::new(1, 1)).unwrap();
+        assert_eq!(resp["error"]["code"], json!(-32_600));
+        assert_eq!(
+            resp["error"]["message"],
+            json!("Batch size exceeds the limit of 1")
+        );
+    }
+
+    #[test]
+    fn test_batch_with_policy() {
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 2, "method": "blockNumber"}
+        ]"#;
+        let config =
+            BatchConfig::default().set_policy(MethodPolicy::deny_all().allow_namespace("eth"));
+        let resp = process(body, &config).unwrap();
+        assert_eq!(resp[0]["result"], json!("0x10"));
+        assert_eq!(resp[1]["error"]["code"], json!(-32_601));
+    }
+
+    #[test]
+    fn test_batch_strict_validation() {
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": null, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "method": "eth_blockNumber"},
+            {"id": 3, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 4, "method": "eth_blockNumber", "foo": 1},
+            {"jsonrpc": "2.0", "id": 5.5, "method": "eth_blockNumber"}
+        ]"#;
+        let config = BatchConfig::default().set_validation(ValidationMode::Strict);
+        let resp = process(body, &config).unwrap();
+        let outputs = resp.as_array().unwrap();
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(
+            outputs[0],
+            json!({"jsonrpc": "2.0", "id": null, "result": "0x10"})
+        );
+        assert_eq!(outputs[1]["id"], json!(3));
+        assert_eq!(outputs[1]["error"]["code"], json!(-32_600));
+        assert_eq!(outputs[2]["id"], json!(4));
+        assert_eq!(outputs[2]["error"]["code"], json!(-32_600));
+        assert_eq!(outputs[3]["id"], json!(5.5));
+        assert_eq!(outputs[3]["error"]["code"], json!(-32_600));
+
+        let resp = process(body, &BatchConfig::default()).unwrap();
+        assert_eq!(resp.as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_single_and_invalid_json() {
+        let body = r#"{"jsonrpc": "2.0", "id": "a", "method": "eth_blockNumber"}"#;
+        let resp = process(body, &BatchConfig::default()).unwrap();
+        assert_eq!(resp, json!({"jsonrpc": "2.0", "id": "a", "result": "0x10"}));
+
+        let resp = process(r#"[{"jsonrpc": "2.0""#, &BatchConfig::default()).unwrap();
+        assert_eq!(resp["error"]["code"], json!(-32_700));
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Default implementations of the methods which need no backend.
+
+use crate::rpc_request::{
+    eth_getUncleByBlockHashAndIndexParams, eth_getUncleByBlockNumberAndIndexParams,
+    eth_getUncleCountByBlockHashParams, eth_getUncleCountByBlockNumberParams, net_listeningParams,
+    openrpc_document, rpc_discoverParams, rpc_modules, rpc_modulesParams, web3_sha3Params,
+    EthUncle, OpenRpcDocument, RpcModules,
+};
+use crate::rpc_types::{Boolean, Data32, Integer};
+use crate::Error;
+
+pub fn rpc_modules_default(_params: rpc_modulesParams) -> Result<RpcModules, Error> {
+    Ok(rpc_modules())
+}
+
+pub fn rpc_discover_default(_params: rpc_discoverParams) -> Result<OpenRpcDocument, Error> {
+    Ok(openrpc_document())
+}
+
+/// CITA has no uncles, so there is no uncle of any block.
+///
+/// The block is not looked up, a block that does not exist also gets `null`
+/// instead of an error. Override the method to check it against the chain.
+pub fn uncle_by_block_hash_and_index_default(
+    _params: eth_getUncleByBlockHashAndIndexParams,
+) -> Result<EthUncle, Error> {
+    Ok(None)
+}
+
+/// The same as `uncle_by_block_hash_and_index_default`.
+pub fn uncle_by_block_number_and_index_default(
+    _params: eth_getUncleByBlockNumberAndIndexParams,
+) -> Result<EthUncle, Error> {
+    Ok(None)
+}
+
+/// The uncle count is always `0`, the block is not looked up either, so a
+/// block that does not exist gets `0` where geth returns `null`.
+pub fn uncle_count_by_block_hash_default(
+    _params: eth_getUncleCountByBlockHashParams,
+) -> Result<Integer, Error> {
+    Ok(Integer::new(0))
+}
+
+/// The same as `uncle_count_by_block_hash_default`.
+pub fn uncle_count_by_block_number_default(
+    _params: eth_getUncleCountByBlockNumberParams,
+) -> Result<Integer, Error> {
+    Ok(Integer::new(0))
+}
+
+/// The node is always listening for the connections of its peers.
+pub fn net_listening_default(_params: net_listeningParams) -> Result<Boolean, Error> {
+    Ok(Boolean::new(true))
+}
+
+pub fn web3_sha3_default(params: web3_sha3Params) -> Result<Data32, Error> {
+    let data: Vec<u8> = params.0.into();
+    Ok(Data32::new(keccak_hash::keccak(data)))
+}
@@ -0,0 +1,464 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State of the polling filters, kept between `eth_newFilter` and
+//! `eth_getFilterChanges`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ethereum_types::U256;
+use futures::lock::Mutex as AsyncMutex;
+
+use crate::rpc_types::ethereum_types::{EthFilter, EthFilterChanges, EthLog};
+use crate::rpc_types::{BlockNumber, BlockTag, Data32, Quantity};
+use crate::Error;
+
+/// Filters not polled within this timeout are evicted, the same as geth.
+pub const DEFAULT_FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The source of the current time, to evict the idle filters.
+pub type Clock = Box<dyn Fn() -> Instant + Send + Sync>;
+
+/// The chain data needed to compute the changes of filters.
+#[async_trait::async_trait]
+pub trait FilterSource: Send + Sync {
+    /// The height of the latest block.
+    async fn block_number(&self) -> Result<u64, Error>;
+
+    /// The hashes of the blocks in `from..=to`.
+    async fn block_hashes(&self, from: u64, to: u64) -> Result<Vec<Data32>, Error>;
+
+    /// The logs matching the address and topics of the filter in the blocks `from..=to`.
+    async fn logs(&self, filter: &EthFilter, from: u64, to: u64) -> Result<Vec<EthLog>, Error>;
+
+    /// The hashes of the transactions in the pool.
+    async fn pending_transactions(&self) -> Result<Vec<Data32>, Error> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Debug)]
+enum FilterKind {
+    Logs(EthFilter),
+    Blocks,
+    PendingTransactions(HashSet<Data32>),
+}
+
+#[derive(Debug)]
+struct FilterState {
+    kind: FilterKind,
+    /// The last block height returned by the filter.
+    cursor: u64,
+}
+
+#[derive(Debug)]
+struct FilterEntry {
+    state: Arc<AsyncMutex<FilterState>>,
+    last_poll: Instant,
+}
+
+/// Installed filters, backed by a `FilterSource`.
+///
+/// The filters are evicted lazily if idle beyond the timeout, on every call of
+/// the manager, or explicitly by `evict_expired`.
+pub struct FilterManager<S> {
+    source: S,
+    timeout: Duration,
+    clock: Clock,
+    next_id: AtomicU64,
+    filters: Mutex<HashMap<u64, FilterEntry>>,
+}
+
+impl<S: FilterSource> FilterManager<S> {
+    pub fn new(source: S) -> Self {
+        Self::with_timeout(source, DEFAULT_FILTER_TIMEOUT)
+    }
+
+    pub fn with_timeout(source: S, timeout: Duration) -> Self {
+        FilterManager {
+            source,
+            timeout,
+            clock: Box::new(Instant::now),
+            next_id: AtomicU64::new(1),
+            filters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the clock, `Instant::now` by default.
+    pub fn set_clock<F>(mut self, clock: F) -> Self
+    where
+        F: Fn() -> Instant + Send + Sync + 'static,
+    {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// The number of installed filters, including the expired ones not evicted yet.
+    pub fn len(&self) -> usize {
+        self.filters.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub async fn new_filter(&self, filter: EthFilter) -> Result<Quantity, Error> {
+        let latest = self.source.block_number().await?;
+        resolve_range(&filter, latest)?;
+        Ok(self.install(FilterKind::Logs(filter), latest))
+    }
+
+    pub async fn new_block_filter(&self) -> Result<Quantity, Error> {
+        let latest = self.source.block_number().await?;
+        Ok(self.install(FilterKind::Blocks, latest))
+    }
+
+    pub async fn new_pending_transaction_filter(&self) -> Result<Quantity, Error> {
+        let seen = self
+            .source
+            .pending_transactions()
+            .await?
+            .into_iter()
+            .collect();
+        Ok(self.install(FilterKind::PendingTransactions(seen), 0))
+    }
+
+    /// Returns `false` if the filter is not found.
+    pub fn uninstall_filter(&self, id: Quantity) -> bool {
+        self.evict_expired();
+        let id: u64 = id.into();
+        self.filters.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Evict the filters idle beyond the timeout, returns the number of them.
+    pub fn evict_expired(&self) -> usize {
+        let timeout = self.timeout;
+        let now = (self.clock)();
+        let mut filters = self.filters.lock().unwrap();
+        let len = filters.len();
+        filters.retain(|_, entry| now.saturating_duration_since(entry.last_poll) <= timeout);
+        len - filters.len()
+    }
+
+    /// The changes since the last poll.
+    pub async fn filter_changes(&self, id: Quantity) -> Result<EthFilterChanges, Error> {
+        let state = self.touch(id)?;
+        let mut state = state.lock().await;
+        let FilterState { kind, cursor } = &mut *state;
+        match kind {
+            FilterKind::Logs(filter) => {
+                let latest = self.source.block_number().await?;
+                let (from, to) = resolve_range(filter, latest)?;
+                let from = from.max(*cursor + 1);
+                let logs = if from <= to {
+                    self.source.logs(filter, from, to).await?
+                } else {
+                    Vec::new()
+                };
+                *cursor = (*cursor).max(latest);
+                Ok(into_changes(logs, EthFilterChanges::Logs))
+            }
+            FilterKind::Blocks => {
+                let latest = self.source.block_number().await?;
+                let hashes = if *cursor < latest {
+                    self.source.block_hashes(*cursor + 1, latest).await?
+                } else {
+                    Vec::new()
+                };
+                *cursor = (*cursor).max(latest);
+                Ok(into_changes(hashes, EthFilterChanges::Hashes))
+            }
+            FilterKind::PendingTransactions(seen) => {
+                let pending = self.source.pending_transactions().await?;
+                let hashes = pending
+                    .iter()
+                    .filter(|hash| !seen.contains(*hash))
+                    .cloned()
+                    .collect();
+                *seen = pending.into_iter().collect();
+                Ok(into_changes(hashes, EthFilterChanges::Hashes))
+            }
+        }
+    }
+
+    /// All the logs matching a log filter, regardless of the cursor.
+    pub async fn filter_logs(&self, id: Quantity) -> Result<Vec<EthLog>, Error> {
+        let state = self.touch(id)?;
+        let state = state.lock().await;
+        match state.kind {
+            FilterKind::Logs(ref filter) => {
+                let latest = self.source.block_number().await?;
+                let (from, to) = resolve_range(filter, latest)?;
+                if from <= to {
+                    self.source.logs(filter, from, to).await
+                } else {
+                    Ok(Vec::new())
+                }
+            }
+            _ => Err(Error::filter_not_found()),
+        }
+    }
+
+    fn install(&self, kind: FilterKind, cursor: u64) -> Quantity {
+        self.evict_expired();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let entry = FilterEntry {
+            state: Arc::new(AsyncMutex::new(FilterState { kind, cursor })),
+            last_poll: (self.clock)(),
+        };
+        self.filters.lock().unwrap().insert(id, entry);
+        Quantity::from(id)
+    }
+
+    // Find the filter and refresh its last poll time.
+    fn touch(&self, id: Quantity) -> Result<Arc<AsyncMutex<FilterState>>, Error> {
+        self.evict_expired();
+        let id: u64 = id.into();
+        let mut filters = self.filters.lock().unwrap();
+        let entry = filters.get_mut(&id).ok_or_else(Error::filter_not_found)?;
+        entry.last_poll = (self.clock)();
+        Ok(Arc::clone(&entry.state))
+    }
+}
+
+fn into_changes<T>(items: Vec<T>, wrap: fn(Vec<T>) -> EthFilterChanges) -> EthFilterChanges {
+    if items.is_empty() {
+        EthFilterChanges::Empty
+    } else {
+        wrap(items)
+    }
+}
+
+fn resolve_height(block: &BlockNumber, latest: u64) -> Result<u64, Error> {
+    match block {
+        BlockNumber::Height(height) if height.0 > U256::from(u64::MAX) => {
+            Err(Error::invalid_params("block number is too large"))
+        }
+        BlockNumber::Height(height) => Ok(height.0.as_u64()),
+        BlockNumber::Tag(BlockTag::Earliest) => Ok(0),
+        BlockNumber::Tag(_) => Ok(latest),
+        BlockNumber::Hash(_) => Err(Error::invalid_params(
+            "block hash is not supported in the filter range",
+        )),
+    }
+}
+
+// Resolve the block range of the filter, the upper bound is capped to the latest block.
+fn resolve_range(filter: &EthFilter, latest: u64) -> Result<(u64, u64), Error> {
+    let from = resolve_height(&filter.from_block, latest)?;
+    let to = resolve_height(&filter.to_block, latest)?.min(latest);
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_types::{Data, VariadicValue};
+    use ethereum_types::{H160, H256, U256};
+    use futures::executor::block_on;
+
+    // One log in every block, emitted by the address of the block height.
+    #[derive(Default)]
+    struct Chain {
+        height: Mutex<u64>,
+        pending: Mutex<Vec<Data32>>,
+    }
+
+    impl Chain {
+        fn mine(&self, blocks: u64) {
+            *self.height.lock().unwrap() += blocks;
+        }
+
+        fn log(height: u64) -> EthLog {
+            EthLog {
+                address: H160::from_low_u64_be(height % 2),
+                topics: vec![],
+                data: Data::new(vec![]),
+                block_hash: H256::from_low_u64_be(height),
+                block_number: height.into(),
+                transaction_hash: H256::zero(),
+                transaction_index: 0u64.into(),
+                log_index: U256::zero(),
+                removed: false,
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FilterSource for Chain {
+        async fn block_number(&self) -> Result<u64, Error> {
+            Ok(*self.height.lock().unwrap())
+        }
+
+        async fn block_hashes(&self, from: u64, to: u64) -> Result<Vec<Data32>, Error> {
+            Ok((from..=to)
+                .map(|h| Data32::new(H256::from_low_u64_be(h)))
+                .collect())
+        }
+
+        async fn logs(&self, filter: &EthFilter, from: u64, to: u64) -> Result<Vec<EthLog>, Error> {
+            Ok((from..=to)
+                .map(Chain::log)
+                .filter(|log| match filter.address {
+                    Some(VariadicValue::Single(ref address)) => {
+                        let address: H160 = address.clone().into();
+                        address == log.address
+                    }
+                    _ => true,
+                })
+                .collect())
+        }
+
+        async fn pending_transactions(&self) -> Result<Vec<Data32>, Error> {
+            Ok(self.pending.lock().unwrap().clone())
+        }
+    }
+
+    fn heights(changes: EthFilterChanges) -> Vec<u64> {
+        match changes {
+            EthFilterChanges::Logs(logs) => logs
+                .into_iter()
+                .map(|log| log.block_number.as_u64())
+                .collect(),
+            EthFilterChanges::Empty => vec![],
+            EthFilterChanges::Hashes(_) => panic!("should be logs"),
+        }
+    }
+
+    #[test]
+    fn test_log_filter() {
+        let manager = FilterManager::new(Chain::default());
+        manager.source().mine(10);
+        let filter = EthFilter::new(
+            BlockNumber::new(5u64.into()),
+            BlockNumber::new(14u64.into()),
+            Some(VariadicValue::single(H160::from_low_u64_be(1).into())),
+            None,
+        );
+        let id = block_on(manager.new_filter(filter)).unwrap();
+
+        assert_eq!(
+            heights(block_on(manager.filter_changes(id.clone())).unwrap()),
+            Vec::<u64>::new()
+        );
+        manager.source().mine(3);
+        assert_eq!(
+            heights(block_on(manager.filter_changes(id.clone())).unwrap()),
+            vec![11, 13]
+        );
+        manager.source().mine(5);
+        assert_eq!(
+            heights(block_on(manager.filter_changes(id.clone())).unwrap()),
+            Vec::<u64>::new()
+        );
+
+        let logs = block_on(manager.filter_logs(id.clone())).unwrap();
+        let heights: Vec<u64> = logs.iter().map(|log| log.block_number.as_u64()).collect();
+        assert_eq!(heights, vec![5, 7, 9, 11, 13]);
+
+        assert!(manager.uninstall_filter(id.clone()));
+        assert!(!manager.uninstall_filter(id.clone()));
+        assert_eq!(
+            block_on(manager.filter_changes(id)).unwrap_err(),
+            Error::filter_not_found()
+        );
+    }
+
+    #[test]
+    fn test_filter_block_number_too_large() {
+        let manager = FilterManager::new(Chain::default());
+        let too_large = U256::from(u64::MAX) + 1;
+        for (from, to) in [(too_large, U256::from(1)), (U256::from(1), too_large)] {
+            let filter = EthFilter::new(
+                BlockNumber::new(from.into()),
+                BlockNumber::new(to.into()),
+                None,
+                None,
+            );
+            assert_eq!(
+                block_on(manager.new_filter(filter)).unwrap_err(),
+                Error::invalid_params("block number is too large")
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_and_pending_transaction_filters() {
+        let manager = FilterManager::new(Chain::default());
+        let blocks = block_on(manager.new_block_filter()).unwrap();
+        manager.source().mine(2);
+        assert_eq!(
+            block_on(manager.filter_changes(blocks.clone())).unwrap(),
+            EthFilterChanges::Hashes(vec![
+                Data32::new(H256::from_low_u64_be(1)),
+                Data32::new(H256::from_low_u64_be(2)),
+            ])
+        );
+        assert_eq!(
+            block_on(manager.filter_changes(blocks.clone())).unwrap(),
+            EthFilterChanges::Empty
+        );
+        assert_eq!(
+            block_on(manager.filter_logs(blocks)).unwrap_err(),
+            Error::filter_not_found()
+        );
+
+        let tx = |n| Data32::new(H256::from_low_u64_be(n));
+        manager.source().pending.lock().unwrap().push(tx(1));
+        let pending = block_on(manager.new_pending_transaction_filter()).unwrap();
+        manager.source().pending.lock().unwrap().push(tx(2));
+        assert_eq!(
+            block_on(manager.filter_changes(pending.clone())).unwrap(),
+            EthFilterChanges::Hashes(vec![tx(2)])
+        );
+        assert_eq!(
+            block_on(manager.filter_changes(pending)).unwrap(),
+            EthFilterChanges::Empty
+        );
+    }
+
+    #[test]
+    fn test_evict_idle_filters() {
+        let start = Instant::now();
+        let elapsed = Arc::new(Mutex::new(Duration::ZERO));
+        let clock = Arc::clone(&elapsed);
+        let manager = FilterManager::with_timeout(Chain::default(), Duration::from_secs(50))
+            .set_clock(move || start + *clock.lock().unwrap());
+        let advance = |secs| *elapsed.lock().unwrap() += Duration::from_secs(secs);
+        let idle = block_on(manager.new_block_filter()).unwrap();
+        let active = block_on(manager.new_block_filter()).unwrap();
+        assert_eq!(manager.len(), 2);
+
+        advance(30);
+        block_on(manager.filter_changes(active.clone())).unwrap();
+        advance(20);
+        assert_eq!(manager.evict_expired(), 0);
+        advance(1);
+
+        assert_eq!(manager.evict_expired(), 1);
+        assert_eq!(manager.len(), 1);
+        assert_eq!(
+            block_on(manager.filter_changes(idle)).unwrap_err(),
+            Error::filter_not_found()
+        );
+        assert!(block_on(manager.filter_changes(active)).is_ok());
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::internals::construct_handler;
+use crate::rpc_handler::defaults;
+use crate::rpc_request::OpenRpcDocument;
+use crate::rpc_request::{
+    debug_traceBlockByNumberParams, debug_traceCallParams, debug_traceTransactionParams,
+    eth_accountsParams, eth_blockNumberParams, eth_callParams, eth_chainIdParams,
+    eth_createAccessListParams, eth_estimateGasParams, eth_feeHistoryParams, eth_gasPriceParams,
+    eth_getBalanceParams, eth_getBlockByHashParams, eth_getBlockByNumberParams,
+    eth_getBlockReceiptsParams, eth_getBlockTransactionCountByHashParams,
+    eth_getBlockTransactionCountByNumberParams, eth_getCodeParams, eth_getFilterChangesParams,
+    eth_getFilterLogsParams, eth_getLogsParams, eth_getProofParams, eth_getStorageAtParams,
+    eth_getTransactionByBlockHashAndIndexParams, eth_getTransactionByBlockNumberAndIndexParams,
+    eth_getTransactionByHashParams, eth_getTransactionCountParams, eth_getTransactionReceiptParams,
+    eth_getUncleByBlockHashAndIndexParams, eth_getUncleByBlockNumberAndIndexParams,
+    eth_getUncleCountByBlockHashParams, eth_getUncleCountByBlockNumberParams,
+    eth_maxPriorityFeePerGasParams, eth_newBlockFilterParams, eth_newFilterParams,
+    eth_newPendingTransactionFilterParams, eth_sendRawTransactionParams, eth_sendTransactionParams,
+    eth_signParams, eth_signTransactionParams, eth_signTypedData_v4Params, eth_subscribeParams,
+    eth_syncingParams, eth_uninstallFilterParams, eth_unsubscribeParams, net_listeningParams,
+    net_peerCountParams, net_versionParams, personal_signParams, rpc_discoverParams,
+    rpc_modulesParams, trace_blockParams, trace_callParams, trace_filterParams,
+    trace_replayTransactionParams, trace_transactionParams, txpool_contentParams,
+    txpool_inspectParams, txpool_statusParams, web3_clientVersionParams, web3_sha3Params,
+    BlockNumberParams, CallParams, EstimateQuotaParams, GetAbiParams, GetBalanceParams,
+    GetBlockByHashParams, GetBlockByNumberParams, GetBlockHeaderParams, GetCensoredAddrsParams,
+    GetCodeParams, GetFilterChangesParams, GetFilterLogsParams, GetLogsParams, GetMetaDataParams,
+    GetPoolTxNumParams, GetStateProofParams, GetStorageKeyParams, GetTransactionCountParams,
+    GetTransactionParams, GetTransactionProofParams, GetTransactionReceiptParams, GetVersionParams,
+    LicenseInfoParams, NewBlockFilterParams, NewFilterParams, OpCensoredAddressParams,
+    PeerCountParams, PeersInfoParams, SendRawTransactionParams, SendTransactionParams,
+    UninstallFilterParams,
+};
+use crate::rpc_request::{
+    Accounts, BlockTraces, Call, EthLogs, EthReceipts, EthUncle, LocalizedTraces, Logs, Request,
+    ResponseResult, RpcModules,
+};
+use crate::rpc_response::{Output, RpcFailure, RpcSuccess};
+use crate::rpc_types::ethereum_types::{
+    AccessListWithGasUsed, DebugTrace, EthAccountProof, EthBlock, EthFilterChanges, EthReceipt,
+    EthRpcTransaction, FeeHistory, SubscriptionId, TxpoolContent, TxpoolInspect, TxpoolStatus,
+};
+use crate::rpc_types::parity_types::trace::TraceResults;
+use crate::rpc_types::{
+    Block, Boolean, CallResult, CensorAddrs, Data, Data32, FilterChanges, Integer, LicenseInfo,
+    MetaData, PeersInfo, PoolTxNum, Quantity, Receipt, RpcTransaction, SoftwareVersion, TxResponse,
+};
+use crate::{impl_for_each_jsonrpc_requests, Error};
+
+macro_rules! define_handler {
+    ($($entries:tt)*) => {
+        construct_handler!(
+            defaults {
+                rpc_modules => defaults::rpc_modules_default,
+                rpc_discover => defaults::rpc_discover_default,
+                eth_getUncleByBlockHashAndIndex => defaults::uncle_by_block_hash_and_index_default,
+                eth_getUncleByBlockNumberAndIndex => defaults::uncle_by_block_number_and_index_default,
+                eth_getUncleCountByBlockHash => defaults::uncle_count_by_block_hash_default,
+                eth_getUncleCountByBlockNumber => defaults::uncle_count_by_block_number_default,
+                net_listening => defaults::net_listening_default,
+                web3_sha3 => defaults::web3_sha3_default,
+            }
+            $($entries)*
+        );
+    };
+}
+
+impl_for_each_jsonrpc_requests!(define_handler);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_complete::complete::Complete;
+    use crate::rpc_request::PartialRequest;
+    use crate::rpc_types::{Id, Version};
+    use futures::executor::block_on;
+    use serde_json::Value;
+
+    struct BlockNumberOnly;
+
+    #[async_trait::async_trait]
+    impl EthRpcHandler for BlockNumberOnly {
+        async fn eth_block_number(
+            &self,
+            _params: eth_blockNumberParams,
+        ) -> Result<Quantity, Error> {
+            Ok(Quantity::from(16u64))
+        }
+    }
+
+    #[test]
+    fn test_dispatch_implemented_method() {
+        let req = eth_blockNumberParams::new().into_request(1);
+        let output = block_on(BlockNumberOnly.dispatch(req));
+
+        let expected = RpcSuccess::new(crate::rpc_request::RequestInfo::new(
+            Some(Version::V2),
+            Id::Num(1),
+        ))
+        .set_result(ResponseResult::eth_blockNumber(16u64.into()))
+        .output();
+        assert_eq!(output, expected);
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#
+        );
+    }
+
+    #[test]
+    fn test_dispatch_default_method_not_found() {
+        let req = eth_chainIdParams::new().into_request(2);
+        let output = block_on(BlockNumberOnly.dispatch(req));
+
+        match output {
+            Output::Failure(failure) => {
+                assert_eq!(failure.id, Id::Num(2));
+                assert_eq!(failure.error, Error::method_not_found());
+            }
+            Output::Success(_) => panic!("unimplemented method should fail"),
+        }
+    }
+
+    #[test]
+    fn test_dispatch_default_rpc_modules() {
+        let req = rpc_modulesParams::new().into_request(3);
+        let output = block_on(BlockNumberOnly.dispatch(req));
+        let output = serde_json::to_value(output).unwrap();
+        assert_eq!(output["result"]["eth"], json!("1.0"));
+        assert_eq!(output["result"]["net"], json!("1.0"));
+    }
+
+    #[test]
+    fn test_dispatch_default_rpc_discover() {
+        let req_str = r#"{"jsonrpc": "2.0", "id": 4, "method": "rpc.discover"}"#;
+        let req = serde_json::from_str::<PartialRequest>(req_str)
+            .unwrap()
+            .complete()
+            .unwrap();
+        let output = block_on(BlockNumberOnly.dispatch(req));
+        let output = serde_json::to_value(output).unwrap();
+        assert_eq!(output["result"]["openrpc"], json!("1.2.6"));
+    }
+
+    #[test]
+    fn test_dispatch_default_uncles() {
+        let requests = [
+            (
+                r#"{"jsonrpc": "2.0", "id": 5, "method": "eth_getUncleByBlockHashAndIndex", "params": ["0x000000000000000000000000000000000000000000000000000000000000000a", "0x0"]}"#,
+                Value::Null,
+            ),
+            (
+                r#"{"jsonrpc": "2.0", "id": 5, "method": "eth_getUncleByBlockNumberAndIndex", "params": ["latest", "0x0"]}"#,
+                Value::Null,
+            ),
+            (
+                r#"{"jsonrpc": "2.0", "id": 5, "method": "eth_getUncleCountByBlockHash", "params": ["0x000000000000000000000000000000000000000000000000000000000000000a"]}"#,
+                json!(0),
+            ),
+            (
+                r#"{"jsonrpc": "2.0", "id": 5, "method": "eth_getUncleCountByBlockNumber", "params": ["0x10"]}"#,
+                json!(0),
+            ),
+        ];
+        for (req_str, result) in requests.iter() {
+            let req = serde_json::from_str::<PartialRequest>(req_str)
+                .unwrap()
+                .complete()
+                .unwrap();
+            let output = block_on(BlockNumberOnly.dispatch(req));
+            let output = serde_json::to_value(output).unwrap();
+            assert_eq!(output.get("result"), Some(result), "{}", req_str);
+        }
+    }
+
+    #[test]
+    fn test_dispatch_default_web3_and_net() {
+        let requests = [
+            (
+                r#"{"jsonrpc": "2.0", "id": 6, "method": "web3_sha3", "params": ["0x68656c6c6f20776f726c64"]}"#,
+                json!("0x47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"),
+            ),
+            (
+                r#"{"jsonrpc": "2.0", "id": 6, "method": "net_listening"}"#,
+                json!(true),
+            ),
+        ];
+        for (req_str, result) in requests.iter() {
+            let req = serde_json::from_str::<PartialRequest>(req_str)
+                .unwrap()
+                .complete()
+                .unwrap();
+            let output = block_on(BlockNumberOnly.dispatch(req));
+            let output = serde_json::to_value(output).unwrap();
+            assert_eq!(output.get("result"), Some(result), "{}", req_str);
+        }
+
+        let req = web3_clientVersionParams::new().into_request(7);
+        match block_on(BlockNumberOnly.dispatch(req)) {
+            Output::Failure(failure) => assert_eq!(failure.error, Error::method_not_found()),
+            Output::Success(_) => panic!("web3_clientVersion needs the backend"),
+        }
+    }
+}
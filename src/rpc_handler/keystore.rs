@@ -0,0 +1,705 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local accounts kept in a directory of key files, in the format of Web3
+//! Secret Storage version 3.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+
+use aes::Aes128;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use ethereum_types::{Address, H256};
+use futures::channel::oneshot;
+use keccak_hash::keccak;
+use rustc_serialize::hex::{FromHex, ToHex};
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use web3::signing::{Key, SecretKey, SecretKeyRef, Signature};
+
+use crate::rpc_handler::signer::Signer;
+use crate::Error;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+const CIPHER: &str = "aes-128-ctr";
+const PRF: &str = "hmac-sha256";
+const DKLEN: u32 = 32;
+
+// Limits of the key derivation parameters in the key files, so a bad file can
+// not take unbounded memory or time when decrypted. The scrypt memory is
+// `128 * r * n`, 256 MiB with the parameters of geth.
+const MAX_DKLEN: u32 = 64;
+const MAX_SCRYPT_MEMORY: u64 = 256 << 20;
+const MAX_SCRYPT_P: u32 = 4;
+const MAX_PBKDF2_ROUNDS: u32 = 1 << 22;
+
+// The decryptions of `sign_hash` run at most on these threads, each may take
+// the scrypt memory above, and wait in a queue of this length.
+const DECRYPTION_THREADS: usize = 2;
+const DECRYPTION_QUEUE_LEN: usize = 64;
+
+/// Bytes as a hex string without `0x`, as in the key files.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl Serialize for HexBytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value
+            .trim_start_matches("0x")
+            .from_hex()
+            .map(HexBytes)
+            .map_err(|_| de::Error::custom(format!("invalid hexadecimal string: {}", value)))
+    }
+}
+
+/// The key derivation function and its parameters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kdf", content = "kdfparams", rename_all = "lowercase")]
+pub enum Kdf {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        r: u32,
+        p: u32,
+        salt: HexBytes,
+    },
+    Pbkdf2 {
+        c: u32,
+        dklen: u32,
+        prf: String,
+        salt: HexBytes,
+    },
+}
+
+impl Kdf {
+    /// Scrypt with the parameters of geth, which takes about one second.
+    pub fn scrypt() -> Self {
+        Self::scrypt_with(1 << 18, 8, 1)
+    }
+
+    /// `n` should be a power of 2.
+    pub fn scrypt_with(n: u32, r: u32, p: u32) -> Self {
+        Kdf::Scrypt {
+            dklen: DKLEN,
+            n,
+            r,
+            p,
+            salt: random_bytes(32),
+        }
+    }
+
+    pub fn pbkdf2(c: u32) -> Self {
+        Kdf::Pbkdf2 {
+            c,
+            dklen: DKLEN,
+            prf: PRF.to_owned(),
+            salt: random_bytes(32),
+        }
+    }
+
+    // The same parameters with a new salt.
+    fn renew_salt(&self) -> Self {
+        let mut kdf = self.clone();
+        match kdf {
+            Kdf::Scrypt { ref mut salt, .. } | Kdf::Pbkdf2 { ref mut salt, .. } => {
+                *salt = random_bytes(32);
+            }
+        }
+        kdf
+    }
+
+    fn derive_key(&self, password: &str) -> Result<Vec<u8>, Error> {
+        match self {
+            Kdf::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                let memory = 128 * u64::from(*r) * u64::from(*n);
+                if !(DKLEN..=MAX_DKLEN).contains(dklen)
+                    || !n.is_power_of_two()
+                    || memory > MAX_SCRYPT_MEMORY
+                    || *p > MAX_SCRYPT_P
+                {
+                    return Err(Error::signer_error("invalid scrypt parameters"));
+                }
+                let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p, *dklen as usize)
+                    .map_err(|_| Error::signer_error("invalid scrypt parameters"))?;
+                let mut key = vec![0u8; *dklen as usize];
+                scrypt::scrypt(password.as_bytes(), &salt.0, &params, &mut key)
+                    .map_err(|_| Error::signer_error("invalid scrypt parameters"))?;
+                Ok(key)
+            }
+            Kdf::Pbkdf2 {
+                c,
+                dklen,
+                prf,
+                salt,
+            } => {
+                if !(DKLEN..=MAX_DKLEN).contains(dklen) || *c > MAX_PBKDF2_ROUNDS || prf != PRF {
+                    return Err(Error::signer_error("invalid pbkdf2 parameters"));
+                }
+                let mut key = vec![0u8; *dklen as usize];
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt.0, *c, &mut key);
+                Ok(key)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CipherParams {
+    pub iv: HexBytes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Crypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    pub ciphertext: HexBytes,
+    #[serde(flatten)]
+    pub kdf: Kdf,
+    pub mac: HexBytes,
+}
+
+/// The key file of Web3 Secret Storage version 3.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KeyFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<HexBytes>,
+    #[serde(alias = "Crypto")]
+    pub crypto: Crypto,
+    pub id: String,
+    pub version: u32,
+}
+
+fn random_bytes(len: usize) -> HexBytes {
+    HexBytes((0..len).map(|_| rand::random::<u8>()).collect())
+}
+
+fn apply_cipher(key: &[u8], iv: &[u8], data: &mut [u8]) -> Result<(), Error> {
+    let mut cipher = Aes128Ctr::new_from_slices(key, iv)
+        .map_err(|_| Error::signer_error("invalid cipher parameters"))?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+fn mac(derived_key: &[u8], ciphertext: &[u8]) -> H256 {
+    keccak([&derived_key[16..32], ciphertext].concat())
+}
+
+// Compare in constant time, not to leak how much of the MAC matches.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+impl KeyFile {
+    /// Encrypt the secret key with the password.
+    pub fn encrypt(secret: &SecretKey, password: &str, kdf: &Kdf) -> Result<Self, Error> {
+        let kdf = kdf.renew_salt();
+        let derived_key = kdf.derive_key(password)?;
+        let iv = random_bytes(16);
+        let mut ciphertext = secret.secret_bytes().to_vec();
+        apply_cipher(&derived_key[..16], &iv.0, &mut ciphertext)?;
+        Ok(KeyFile {
+            address: Some(HexBytes(
+                SecretKeyRef::new(secret).address().as_bytes().to_vec(),
+            )),
+            crypto: Crypto {
+                cipher: CIPHER.to_owned(),
+                cipherparams: CipherParams { iv },
+                mac: HexBytes(mac(&derived_key, &ciphertext).as_bytes().to_vec()),
+                ciphertext: HexBytes(ciphertext),
+                kdf,
+            },
+            id: uuid::Uuid::new_v4().to_string(),
+            version: 3,
+        })
+    }
+
+    /// Decrypt the secret key with the password.
+    pub fn decrypt(&self, password: &str) -> Result<SecretKey, Error> {
+        if self.version != 3 || self.crypto.cipher != CIPHER {
+            return Err(Error::signer_error("unsupported key file"));
+        }
+        let derived_key = self.crypto.kdf.derive_key(password)?;
+        let ciphertext = &self.crypto.ciphertext.0;
+        if !constant_time_eq(mac(&derived_key, ciphertext).as_bytes(), &self.crypto.mac.0) {
+            return Err(Error::signer_error(
+                "could not decrypt key with given password",
+            ));
+        }
+        let mut secret = ciphertext.clone();
+        apply_cipher(
+            &derived_key[..16],
+            &self.crypto.cipherparams.iv.0,
+            &mut secret,
+        )?;
+        SecretKey::from_slice(&secret).map_err(|_| Error::signer_error("invalid secret key"))
+    }
+
+    /// The address in the key file, which is optional.
+    pub fn address(&self) -> Option<Address> {
+        self.address
+            .as_ref()
+            .filter(|address| address.0.len() == 20)
+            .map(|address| Address::from_slice(&address.0))
+    }
+}
+
+#[derive(Debug)]
+struct Account {
+    key_file: KeyFile,
+    unlocked: Option<SecretKey>,
+}
+
+/// The accounts in a directory of key files.
+///
+/// The accounts are locked after loaded, and an account signs only if it is
+/// unlocked, or with the password for one signature.
+///
+/// The key files are decrypted outside the lock of the accounts, which takes
+/// about one second with the default scrypt. The synchronous methods run it on
+/// the calling thread, and `sign_hash` on a bounded number of threads, not to
+/// block the executor. `sign_hash` fails if too many decryptions are waiting.
+#[derive(Debug)]
+pub struct Keystore {
+    dir: PathBuf,
+    kdf: Kdf,
+    accounts: Mutex<HashMap<Address, Account>>,
+    decryptor: Decryptor,
+}
+
+impl Keystore {
+    /// Load the key files in the directory, which is created if missing. The
+    /// files which are not key files or without the address are skipped.
+    pub fn open<P: Into<PathBuf>>(dir: P) -> Result<Self, Error> {
+        let dir = dir.into();
+        let io_error = |err: std::io::Error| Error::signer_error(format!("keystore: {}", err));
+        fs::create_dir_all(&dir).map_err(io_error)?;
+        let mut accounts = HashMap::new();
+        for entry in fs::read_dir(&dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let key_file = match fs::read(&path)
+                .ok()
+                .and_then(|content| serde_json::from_slice::<KeyFile>(&content).ok())
+            {
+                Some(key_file) => key_file,
+                None => continue,
+            };
+            if let Some(address) = key_file.address() {
+                accounts.insert(
+                    address,
+                    Account {
+                        key_file,
+                        unlocked: None,
+                    },
+                );
+            }
+        }
+        Ok(Keystore {
+            dir,
+            kdf: Kdf::scrypt(),
+            accounts: Mutex::new(accounts),
+            decryptor: Decryptor::new(DECRYPTION_THREADS, DECRYPTION_QUEUE_LEN),
+        })
+    }
+
+    /// The key derivation function of the new accounts.
+    pub fn set_kdf(mut self, kdf: Kdf) -> Self {
+        self.kdf = kdf;
+        self
+    }
+
+    /// The number of the key files `sign_hash` decrypts at the same time, 2 by
+    /// default.
+    pub fn set_decryption_threads(mut self, threads: usize) -> Self {
+        self.decryptor = Decryptor::new(threads, DECRYPTION_QUEUE_LEN);
+        self
+    }
+
+    /// Create an account with a random key.
+    pub fn new_account(&self, password: &str) -> Result<Address, Error> {
+        let secret = SecretKey::new(&mut rand::thread_rng());
+        self.import(&secret, password)
+    }
+
+    /// Import the secret key, and save it in a new key file.
+    pub fn import(&self, secret: &SecretKey, password: &str) -> Result<Address, Error> {
+        let key_file = KeyFile::encrypt(secret, password, &self.kdf)?;
+        let address = SecretKeyRef::new(secret).address();
+        let content = serde_json::to_vec(&key_file).unwrap();
+        write_key_file(&self.dir.join(format!("{:x}.json", address)), &content)
+            .map_err(|err| Error::signer_error(format!("keystore: {}", err)))?;
+        self.accounts.lock().unwrap().insert(
+            address,
+            Account {
+                key_file,
+                unlocked: None,
+            },
+        );
+        Ok(address)
+    }
+
+    pub fn unlock(&self, address: &Address, password: &str) -> Result<(), Error> {
+        let secret = decrypt_account(&self.key_file(address)?, address, password)?;
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts
+            .get_mut(address)
+            .ok_or_else(Error::unknown_account)?;
+        account.unlocked = Some(secret);
+        Ok(())
+    }
+
+    // Clone the key file, to decrypt it without the lock.
+    fn key_file(&self, address: &Address) -> Result<KeyFile, Error> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .get(address)
+            .map(|account| account.key_file.clone())
+            .ok_or_else(Error::unknown_account)
+    }
+
+    /// Returns `false` if the account is not found.
+    pub fn lock(&self, address: &Address) -> bool {
+        match self.accounts.lock().unwrap().get_mut(address) {
+            Some(account) => {
+                account.unlocked = None;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+type DecryptionJob = Box<dyn FnOnce() + Send>;
+
+// The threads decrypting the key files for `sign_hash`, started on the first
+// job and stopped when the keystore is dropped.
+#[derive(Debug)]
+struct Decryptor {
+    threads: usize,
+    queue_len: usize,
+    sender: OnceLock<mpsc::SyncSender<DecryptionJob>>,
+}
+
+impl Decryptor {
+    fn new(threads: usize, queue_len: usize) -> Self {
+        Decryptor {
+            threads: threads.max(1),
+            queue_len,
+            sender: OnceLock::new(),
+        }
+    }
+
+    fn spawn(&self, job: DecryptionJob) -> Result<(), Error> {
+        let sender = self.sender.get_or_init(|| {
+            let (sender, receiver) = mpsc::sync_channel::<DecryptionJob>(self.queue_len);
+            let receiver = Arc::new(Mutex::new(receiver));
+            for _ in 0..self.threads {
+                let receiver = receiver.clone();
+                thread::spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                });
+            }
+            sender
+        });
+        sender
+            .try_send(job)
+            .map_err(|_| Error::signer_error("keystore: too many decryptions"))
+    }
+}
+
+// Decrypt the key file of the account, the key should be of the address the
+// account is stored under.
+fn decrypt_account(
+    key_file: &KeyFile,
+    address: &Address,
+    password: &str,
+) -> Result<SecretKey, Error> {
+    let secret = key_file.decrypt(password)?;
+    if SecretKeyRef::new(&secret).address() != *address {
+        return Err(Error::signer_error("key content mismatch"));
+    }
+    Ok(secret)
+}
+
+// Only the owner can read the key file, as geth.
+fn write_key_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content)
+}
+
+#[async_trait::async_trait]
+impl Signer for Keystore {
+    async fn accounts(&self) -> Result<Vec<Address>, Error> {
+        let mut accounts: Vec<Address> = self.accounts.lock().unwrap().keys().cloned().collect();
+        accounts.sort();
+        Ok(accounts)
+    }
+
+    async fn sign_hash(
+        &self,
+        address: &Address,
+        hash: &H256,
+        password: Option<&str>,
+    ) -> Result<Signature, Error> {
+        let secret = match password {
+            Some(password) => {
+                let key_file = self.key_file(address)?;
+                let (address, password) = (*address, password.to_owned());
+                let (sender, receiver) = oneshot::channel();
+                self.decryptor.spawn(Box::new(move || {
+                    let _ = sender.send(decrypt_account(&key_file, &address, &password));
+                }))?;
+                receiver
+                    .await
+                    .map_err(|_| Error::signer_error("keystore: decryption aborted"))??
+            }
+            None => {
+                let accounts = self.accounts.lock().unwrap();
+                let account = accounts.get(address).ok_or_else(Error::unknown_account)?;
+                account.unlocked.ok_or_else(Error::account_locked)?
+            }
+        };
+        SecretKeyRef::new(&secret)
+            .sign_message(hash.as_bytes())
+            .map_err(|err| Error::signer_error(format!("{}", err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("keystore-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_decrypt_pbkdf2() {
+        // The test vector of Web3 Secret Storage.
+        let key_file = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#;
+        let key_file: KeyFile = serde_json::from_str(key_file).unwrap();
+        assert_eq!(key_file.address(), None);
+        let secret = key_file.decrypt("testpassword").unwrap();
+        assert_eq!(
+            secret.secret_bytes().to_hex(),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+        assert_eq!(
+            SecretKeyRef::new(&secret).address(),
+            "008aeeda4d805471df9b2a5b0f38a0c3bcba786b".parse().unwrap()
+        );
+        assert!(key_file.decrypt("wrongpassword").is_err());
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        for kdf in [Kdf::scrypt_with(1 << 10, 8, 1), Kdf::pbkdf2(1024)] {
+            let key_file = KeyFile::encrypt(&secret, "password", &kdf).unwrap();
+            let json = serde_json::to_string(&key_file).unwrap();
+            let key_file: KeyFile = serde_json::from_str(&json).unwrap();
+            assert_eq!(key_file.crypto.kdf.derive_key("").unwrap().len(), 32);
+            assert_eq!(key_file.decrypt("password").unwrap(), secret);
+            assert_eq!(
+                key_file.address(),
+                Some(SecretKeyRef::new(&secret).address())
+            );
+        }
+    }
+
+    #[test]
+    fn test_kdf_limits() {
+        let scrypt = |n, r, p, dklen| Kdf::Scrypt {
+            dklen,
+            n,
+            r,
+            p,
+            salt: random_bytes(32),
+        };
+        assert!(scrypt(1 << 10, 8, 1, DKLEN).derive_key("").is_ok());
+        assert!(scrypt(1 << 19, 8, 1, DKLEN).derive_key("").is_err());
+        assert!(scrypt(1 << 10, 1 << 20, 1, DKLEN).derive_key("").is_err());
+        assert!(scrypt(1 << 10, 8, 1 << 20, DKLEN).derive_key("").is_err());
+        assert!(scrypt(1 << 10, 8, 1, u32::MAX).derive_key("").is_err());
+
+        let mut pbkdf2 = Kdf::pbkdf2(MAX_PBKDF2_ROUNDS + 1);
+        assert!(pbkdf2.derive_key("").is_err());
+        if let Kdf::Pbkdf2 { ref mut c, .. } = pbkdf2 {
+            *c = 1024;
+        }
+        assert!(pbkdf2.derive_key("").is_ok());
+
+        assert!(constant_time_eq(&[1, 2], &[1, 2]));
+        assert!(!constant_time_eq(&[1, 2], &[1, 3]));
+        assert!(!constant_time_eq(&[1, 2], &[1]));
+    }
+
+    #[test]
+    fn test_keystore() {
+        let dir = temp_dir();
+        let keystore = Keystore::open(&dir)
+            .unwrap()
+            .set_kdf(Kdf::scrypt_with(1 << 10, 8, 1));
+        let address = keystore.new_account("password").unwrap();
+        let hash = H256::repeat_byte(0x33);
+
+        assert_eq!(block_on(keystore.accounts()).unwrap(), vec![address]);
+        assert_eq!(
+            block_on(keystore.sign_hash(&address, &hash, None)).err(),
+            Some(Error::account_locked())
+        );
+        assert!(block_on(keystore.sign_hash(&address, &hash, Some("password"))).is_ok());
+        assert!(block_on(keystore.sign_hash(&address, &hash, Some("wrong"))).is_err());
+        assert_eq!(
+            block_on(keystore.sign_hash(&Address::zero(), &hash, None)).err(),
+            Some(Error::unknown_account())
+        );
+
+        keystore.unlock(&address, "password").unwrap();
+        let signature = block_on(keystore.sign_hash(&address, &hash, None)).unwrap();
+        let mut bytes = signature.r.as_bytes().to_vec();
+        bytes.extend_from_slice(signature.s.as_bytes());
+        assert_eq!(
+            web3::signing::recover(hash.as_bytes(), &bytes, signature.v as i32).unwrap(),
+            address
+        );
+        assert!(keystore.lock(&address));
+        assert!(block_on(keystore.sign_hash(&address, &hash, None)).is_err());
+
+        // The accounts are loaded from the key files.
+        let reopened = Keystore::open(&dir).unwrap();
+        assert_eq!(block_on(reopened.accounts()).unwrap(), vec![address]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_decryptor_queue() {
+        let decryptor = Decryptor::new(1, 1);
+        let (started, wait_started) = mpsc::channel();
+        let (release, wait_release) = mpsc::channel::<()>();
+        decryptor
+            .spawn(Box::new(move || {
+                started.send(()).unwrap();
+                let _ = wait_release.recv();
+            }))
+            .unwrap();
+        wait_started.recv().unwrap();
+
+        // One job runs, one waits, the next is rejected.
+        let (done, wait_done) = mpsc::channel();
+        decryptor
+            .spawn(Box::new(move || done.send(()).unwrap()))
+            .unwrap();
+        assert_eq!(
+            decryptor.spawn(Box::new(|| ())).err(),
+            Some(Error::signer_error("keystore: too many decryptions"))
+        );
+        release.send(()).unwrap();
+        wait_done.recv().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir();
+        let keystore = Keystore::open(&dir)
+            .unwrap()
+            .set_kdf(Kdf::scrypt_with(1 << 10, 8, 1));
+        let address = keystore.new_account("password").unwrap();
+        let path = dir.join(format!("{:x}.json", address));
+        let mode = fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_content_mismatch() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let mut key_file = KeyFile::encrypt(&secret, "password", &Kdf::pbkdf2(1024)).unwrap();
+        let claimed = Address::repeat_byte(0x22);
+        key_file.address = Some(HexBytes(claimed.as_bytes().to_vec()));
+        fs::write(dir.join("key.json"), serde_json::to_vec(&key_file).unwrap()).unwrap();
+
+        let keystore = Keystore::open(&dir).unwrap();
+        assert_eq!(block_on(keystore.accounts()).unwrap(), vec![claimed]);
+        let mismatch = Some(Error::signer_error("key content mismatch"));
+        assert_eq!(keystore.unlock(&claimed, "password").err(), mismatch);
+        let hash = H256::repeat_byte(0x33);
+        assert_eq!(
+            block_on(keystore.sign_hash(&claimed, &hash, Some("password"))).err(),
+            mismatch
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
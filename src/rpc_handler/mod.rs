@@ -0,0 +1,31 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod batch;
+mod defaults;
+mod filter_manager;
+mod handler;
+mod keystore;
+mod signer;
+mod subscription;
+
+pub use self::batch::{process_batch, process_request, BatchConfig};
+pub use self::filter_manager::{Clock, FilterManager, FilterSource, DEFAULT_FILTER_TIMEOUT};
+pub use self::handler::EthRpcHandler;
+pub use self::keystore::{CipherParams, Crypto, HexBytes, Kdf, KeyFile, Keystore};
+pub use self::signer::{
+    encode_signature, eth_accounts, eth_sign, eth_sign_transaction, eth_sign_typed_data_v4,
+    personal_sign, sign_message, sign_transaction, sign_typed_data, Signer,
+};
+pub use self::subscription::{ChainEvent, SubscriptionRegistry};
@@ -0,0 +1,393 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing of messages and transactions with the keys of the accounts, as
+//! `eth_accounts`, `eth_sign`, `personal_sign`, `eth_signTransaction` and
+//! `eth_signTypedData_v4`.
+
+use ethereum_types::{Address, H256, U256};
+use web3::signing::{hash_message, Signature};
+
+use crate::rpc_request::{
+    eth_accountsParams, eth_signParams, eth_signTransactionParams, eth_signTypedData_v4Params,
+    personal_signParams, Accounts,
+};
+use crate::rpc_types::ethereum_types::{Eip712TypedData, EthTransactionRequest};
+use crate::rpc_types::parity_types::{
+    AccessListTx, Action, EIP1559TransactionTx, Transaction, TypedTransaction,
+};
+use crate::rpc_types::{Data, Data20};
+use crate::Error;
+
+/// The accounts able to sign.
+#[async_trait::async_trait]
+pub trait Signer: Send + Sync {
+    /// The addresses of the accounts.
+    async fn accounts(&self) -> Result<Vec<Address>, Error>;
+
+    /// Sign the hash with the key of the account, the `v` of the signature is
+    /// the recovery id (0 or 1).
+    ///
+    /// The password unlocks the account for this signature only, without it the
+    /// account should be unlocked already.
+    async fn sign_hash(
+        &self,
+        address: &Address,
+        hash: &H256,
+        password: Option<&str>,
+    ) -> Result<Signature, Error>;
+}
+
+/// The 65 bytes `r || s || v` of the signature, with `v` of 27 or 28.
+pub fn encode_signature(signature: &Signature) -> Data {
+    let mut bytes = Vec::with_capacity(65);
+    bytes.extend_from_slice(signature.r.as_bytes());
+    bytes.extend_from_slice(signature.s.as_bytes());
+    bytes.push(signature.v as u8 + 27);
+    Data::new(bytes)
+}
+
+/// Sign the message with the prefix of EIP-191.
+pub async fn sign_message<S>(
+    signer: &S,
+    address: &Address,
+    message: &[u8],
+    password: Option<&str>,
+) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    let signature = signer
+        .sign_hash(address, &hash_message(message), password)
+        .await?;
+    Ok(encode_signature(&signature))
+}
+
+// Build the unsigned transaction, the type is decided by the fee fields if it
+// is not given.
+fn typed_transaction(request: &EthTransactionRequest) -> Result<TypedTransaction, Error> {
+    let nonce = request
+        .nonce
+        .clone()
+        .ok_or_else(|| Error::invalid_params("nonce is required"))?;
+    let gas = request
+        .gas
+        .clone()
+        .ok_or_else(|| Error::invalid_params("gas is required"))?;
+    let data = request
+        .input
+        .clone()
+        .or_else(|| request.data.clone())
+        .unwrap_or_default();
+    let gas_price = request
+        .max_fee_per_gas
+        .clone()
+        .or_else(|| request.gas_price.clone())
+        .unwrap_or_default();
+    let tx = Transaction {
+        nonce: nonce.into(),
+        gas_price: gas_price.into(),
+        gas: gas.into(),
+        action: match request.to {
+            Some(ref to) => Action::Call(to.clone().into()),
+            None => Action::Create,
+        },
+        value: request.value.clone().unwrap_or_default().into(),
+        data: data.into(),
+    };
+    let access_list = request
+        .access_list
+        .iter()
+        .flatten()
+        .map(|item| (item.address, item.storage_keys.clone()))
+        .collect();
+    let tx_type = match request.type_ {
+        Some(ref typ) => typ.0,
+        None if request.max_fee_per_gas.is_some() => 2,
+        None if request.access_list.is_some() => 1,
+        None => 0,
+    };
+    match tx_type {
+        0 => Ok(TypedTransaction::Legacy(tx)),
+        1 => Ok(TypedTransaction::AccessList(AccessListTx::new(
+            tx,
+            access_list,
+        ))),
+        2 => Ok(TypedTransaction::EIP1559Transaction(EIP1559TransactionTx {
+            transaction: AccessListTx::new(tx, access_list),
+            max_priority_fee_per_gas: request
+                .max_priority_fee_per_gas
+                .clone()
+                .unwrap_or_default()
+                .into(),
+        })),
+        typ => Err(Error::invalid_params(format!(
+            "transaction type {} is not supported",
+            typ
+        ))),
+    }
+}
+
+/// Sign the transaction of the request, and returns the raw transaction.
+///
+/// The `from`, `nonce` and `gas` of the request are required, and the chain id
+/// of the request should be the same as the one given if it is present.
+pub async fn sign_transaction<S>(
+    signer: &S,
+    request: &EthTransactionRequest,
+    chain_id: u64,
+) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    let from: Address = request
+        .from
+        .clone()
+        .ok_or_else(|| Error::invalid_params("from is required"))?
+        .into();
+    if let Some(ref id) = request.chain_id {
+        if id.0 != U256::from(chain_id) {
+            return Err(Error::invalid_params(format!(
+                "chain id should be {}",
+                chain_id
+            )));
+        }
+    }
+    let tx = typed_transaction(request)?;
+    let hash = tx.signature_hash(Some(chain_id));
+    let signature = signer.sign_hash(&from, &hash, None).await?;
+    Ok(Data::new(
+        tx.with_signature(signature, Some(chain_id)).encode(),
+    ))
+}
+
+/// Sign the typed structured data of EIP-712.
+pub async fn sign_typed_data<S>(
+    signer: &S,
+    address: &Address,
+    typed_data: &Eip712TypedData,
+) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    let hash = typed_data.signing_hash()?;
+    let signature = signer.sign_hash(address, &hash, None).await?;
+    Ok(encode_signature(&signature))
+}
+
+pub async fn eth_accounts<S>(signer: &S, _params: eth_accountsParams) -> Result<Accounts, Error>
+where
+    S: Signer + ?Sized,
+{
+    Ok(signer
+        .accounts()
+        .await?
+        .into_iter()
+        .map(Data20::new)
+        .collect())
+}
+
+pub async fn eth_sign<S>(signer: &S, params: eth_signParams) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    let message: Vec<u8> = params.1.into();
+    sign_message(signer, &params.0.into(), &message, None).await
+}
+
+/// The password is optional, the account should be unlocked without it.
+pub async fn personal_sign<S>(signer: &S, params: personal_signParams) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    let message: Vec<u8> = params.0.into();
+    sign_message(signer, &params.1.into(), &message, params.2.as_deref()).await
+}
+
+pub async fn eth_sign_transaction<S>(
+    signer: &S,
+    params: eth_signTransactionParams,
+    chain_id: u64,
+) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    sign_transaction(signer, &params.0, chain_id).await
+}
+
+pub async fn eth_sign_typed_data_v4<S>(
+    signer: &S,
+    params: eth_signTypedData_v4Params,
+) -> Result<Data, Error>
+where
+    S: Signer + ?Sized,
+{
+    sign_typed_data(signer, &params.0.into(), &params.1).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_types::parity_types::UnverifiedTransaction;
+    use crate::rpc_types::Quantity;
+    use futures::executor::block_on;
+    use web3::signing::{recover, Key, SecretKey, SecretKeyRef};
+
+    struct OneKey(SecretKey);
+
+    #[async_trait::async_trait]
+    impl Signer for OneKey {
+        async fn accounts(&self) -> Result<Vec<Address>, Error> {
+            Ok(vec![SecretKeyRef::new(&self.0).address()])
+        }
+
+        async fn sign_hash(
+            &self,
+            address: &Address,
+            hash: &H256,
+            _password: Option<&str>,
+        ) -> Result<Signature, Error> {
+            if *address != SecretKeyRef::new(&self.0).address() {
+                return Err(Error::unknown_account());
+            }
+            Ok(SecretKeyRef::new(&self.0)
+                .sign_message(hash.as_bytes())
+                .unwrap())
+        }
+    }
+
+    fn one_key() -> (OneKey, Address) {
+        let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let address = SecretKeyRef::new(&secret).address();
+        (OneKey(secret), address)
+    }
+
+    fn sender(tx: &UnverifiedTransaction) -> Address {
+        let (signature, recovery_id) = tx.as_signature();
+        let hash = tx.as_unsigned().signature_hash(tx.chain_id());
+        recover(hash.as_bytes(), &signature, recovery_id).unwrap()
+    }
+
+    #[test]
+    fn test_eth_sign() {
+        let (signer, address) = one_key();
+        let params = eth_signParams::new(address.into(), Data::new(b"hello".to_vec()));
+        let signature: Vec<u8> = block_on(eth_sign(&signer, params)).unwrap().into();
+        assert_eq!(signature.len(), 65);
+        assert!(signature[64] == 27 || signature[64] == 28);
+        let recovered = recover(
+            hash_message(b"hello").as_bytes(),
+            &signature[..64],
+            signature[64] as i32 - 27,
+        )
+        .unwrap();
+        assert_eq!(recovered, address);
+
+        let params = personal_signParams::new(Data::new(b"hello".to_vec()), address.into(), None);
+        let personal: Vec<u8> = block_on(personal_sign(&signer, params)).unwrap().into();
+        assert_eq!(personal, signature);
+
+        let params = eth_signParams::new(Address::zero().into(), Data::new(b"hello".to_vec()));
+        assert_eq!(
+            block_on(eth_sign(&signer, params)),
+            Err(Error::unknown_account())
+        );
+    }
+
+    #[test]
+    fn test_eth_sign_transaction() {
+        let (signer, address) = one_key();
+        let mut request = EthTransactionRequest {
+            from: Some(address.into()),
+            to: Some(Address::repeat_byte(0x22).into()),
+            gas: Some(Quantity::from(21000u64)),
+            value: Some(Quantity::from(1u64)),
+            nonce: Some(Quantity::from(7u64)),
+            ..Default::default()
+        };
+
+        for (typ, max_fee_per_gas) in [(0u8, None), (2u8, Some(Quantity::from(100u64)))] {
+            request.max_fee_per_gas = max_fee_per_gas;
+            let params = eth_signTransactionParams::new(request.clone());
+            let raw: Vec<u8> = block_on(eth_sign_transaction(&signer, params, 1))
+                .unwrap()
+                .into();
+            let tx = TypedTransaction::decode(&raw).unwrap();
+            assert_eq!(tx.as_unsigned().tx_type() as u8, typ);
+            assert_eq!(tx.chain_id(), Some(1));
+            assert_eq!(tx.as_unsigned().tx().nonce, U256::from(7));
+            assert_eq!(sender(&tx), address);
+        }
+
+        request.chain_id = Some(Quantity::from(2u64));
+        let params = eth_signTransactionParams::new(request.clone());
+        assert!(block_on(eth_sign_transaction(&signer, params, 1)).is_err());
+
+        request.chain_id = None;
+        request.nonce = None;
+        let params = eth_signTransactionParams::new(request);
+        assert_eq!(
+            block_on(eth_sign_transaction(&signer, params, 1)),
+            Err(Error::invalid_params("nonce is required"))
+        );
+    }
+
+    #[test]
+    fn test_eth_sign_typed_data_v4() {
+        // The example of EIP-712, signed by the key of "cow".
+        let secret = SecretKey::from_slice(keccak_hash::keccak("cow").as_bytes()).unwrap();
+        let signer = OneKey(secret);
+        let typed_data = json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallet", "type": "address"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person"},
+                    {"name": "contents", "type": "string"}
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                "contents": "Hello, Bob!"
+            }
+        });
+        let params: eth_signTypedData_v4Params = serde_json::from_value(json!([
+            "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826",
+            typed_data
+        ]))
+        .unwrap();
+        let signature = block_on(eth_sign_typed_data_v4(&signer, params)).unwrap();
+        assert_eq!(
+            serde_json::to_value(signature).unwrap(),
+            json!("0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c")
+        );
+    }
+}
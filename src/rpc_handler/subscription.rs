@@ -0,0 +1,291 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subscriptions of `eth_subscribe`, fed with the events of the chain.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::stream::{Stream, StreamExt};
+
+use crate::rpc_types::ethereum_types::{
+    EthBlockHeader, EthFilter, EthLog, SubscriptionId, SubscriptionKind, SubscriptionNotification,
+    SubscriptionResult, SyncingResult,
+};
+use crate::rpc_types::Data32;
+
+/// The events of the chain pushed to the subscribers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainEvent {
+    /// A new block of the canonical chain, with all the logs in it.
+    NewBlock {
+        header: Box<EthBlockHeader>,
+        logs: Vec<EthLog>,
+    },
+    /// The logs of the blocks dropped by a reorg, sent again with `removed: true`.
+    Reorg { removed: Vec<EthLog> },
+    /// A transaction entering the pool.
+    PendingTransaction(Data32),
+    /// The syncing started, progressed or stopped.
+    Syncing(SyncingResult),
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    kind: SubscriptionKind,
+    filter: EthFilter,
+    sender: UnboundedSender<SubscriptionNotification>,
+}
+
+impl Subscriber {
+    fn results(&self, event: &ChainEvent) -> Vec<SubscriptionResult> {
+        match (self.kind, event) {
+            (SubscriptionKind::NewHeads, ChainEvent::NewBlock { header, .. }) => {
+                vec![SubscriptionResult::Header(header.clone())]
+            }
+            (SubscriptionKind::Logs, ChainEvent::NewBlock { logs, .. }) => logs
+                .iter()
+                .filter(|log| self.filter.matches(log))
+                .map(|log| SubscriptionResult::Log(Box::new(log.clone())))
+                .collect(),
+            (SubscriptionKind::Logs, ChainEvent::Reorg { removed }) => removed
+                .iter()
+                .filter(|log| self.filter.matches(log))
+                .map(|log| {
+                    let mut log = log.clone();
+                    log.removed = true;
+                    SubscriptionResult::Log(Box::new(log))
+                })
+                .collect(),
+            (SubscriptionKind::NewPendingTransactions, ChainEvent::PendingTransaction(hash)) => {
+                vec![SubscriptionResult::TransactionHash(hash.clone())]
+            }
+            (SubscriptionKind::Syncing, ChainEvent::Syncing(syncing)) => {
+                vec![SubscriptionResult::Syncing(syncing.clone())]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The active subscriptions, each of them has a channel to its connection.
+///
+/// The subscriptions whose receiver is dropped are removed on the next event.
+#[derive(Debug, Default)]
+pub struct SubscriptionRegistry {
+    subscribers: Mutex<HashMap<SubscriptionId, Subscriber>>,
+}
+
+impl SubscriptionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Install a subscription, the notifications are sent to the returned receiver.
+    ///
+    /// The filter is only used by the `logs` subscriptions, and the block range
+    /// of it is ignored.
+    pub fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+        filter: EthFilter,
+    ) -> (SubscriptionId, UnboundedReceiver<SubscriptionNotification>) {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let mut id = SubscriptionId::random();
+        while subscribers.contains_key(&id) {
+            id = SubscriptionId::random();
+        }
+        subscribers.insert(
+            id.clone(),
+            Subscriber {
+                kind,
+                filter,
+                sender,
+            },
+        );
+        (id, receiver)
+    }
+
+    /// Returns `false` if the subscription is not found.
+    pub fn unsubscribe(&self, id: &SubscriptionId) -> bool {
+        self.subscribers.lock().unwrap().remove(id).is_some()
+    }
+
+    /// Push the event to the matching subscribers, returns the number of
+    /// notifications sent.
+    pub fn on_event(&self, event: &ChainEvent) -> usize {
+        let mut sent = 0;
+        self.subscribers.lock().unwrap().retain(|id, subscriber| {
+            for result in subscriber.results(event) {
+                let notification = SubscriptionNotification::new(id.clone(), result);
+                if subscriber.sender.unbounded_send(notification).is_err() {
+                    return false;
+                }
+                sent += 1;
+            }
+            !subscriber.sender.is_closed()
+        });
+        sent
+    }
+
+    /// Push all the events of the stream, until it is exhausted.
+    pub async fn run<S>(&self, events: S)
+    where
+        S: Stream<Item = ChainEvent>,
+    {
+        futures::pin_mut!(events);
+        while let Some(event) = events.next().await {
+            self.on_event(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_types::ethereum_types::SyncStatus;
+    use crate::rpc_types::{Data, Data20, VariadicValue};
+    use ethereum_types::{H160, H256, U256};
+    use futures::executor::block_on;
+    use futures::stream;
+
+    fn header(number: u64) -> Box<EthBlockHeader> {
+        Box::new(EthBlockHeader {
+            parent_hash: H256::from_low_u64_be(number - 1),
+            sha3_uncles: H256::zero(),
+            miner: H160::zero(),
+            state_root: H256::zero(),
+            transactions_root: H256::zero(),
+            receipts_root: H256::zero(),
+            logs_bloom: Default::default(),
+            difficulty: U256::zero(),
+            number: number.into(),
+            gas_limit: U256::zero(),
+            gas_used: U256::zero(),
+            timestamp: U256::zero(),
+            extra_data: Data::new(vec![]),
+            mix_hash: H256::zero(),
+            nonce: Default::default(),
+            base_fee_per_gas: U256::zero(),
+            hash: H256::from_low_u64_be(number),
+            total_difficulty: U256::zero(),
+        })
+    }
+
+    fn log(address: u64, topic: u64) -> EthLog {
+        EthLog {
+            address: H160::from_low_u64_be(address),
+            topics: vec![H256::from_low_u64_be(topic)],
+            data: Data::new(vec![]),
+            block_hash: H256::from_low_u64_be(1),
+            block_number: 1u64.into(),
+            transaction_hash: H256::zero(),
+            transaction_index: 0u64.into(),
+            log_index: U256::zero(),
+            removed: false,
+        }
+    }
+
+    fn results(
+        receiver: &mut UnboundedReceiver<SubscriptionNotification>,
+    ) -> Vec<SubscriptionResult> {
+        let mut results = Vec::new();
+        while let Ok(notification) = receiver.try_recv() {
+            assert_eq!(notification.method, "eth_subscription");
+            results.push(notification.params.result);
+        }
+        results
+    }
+
+    #[test]
+    fn test_subscription_fan_out() {
+        let registry = SubscriptionRegistry::new();
+        let (_, mut heads) = registry.subscribe(SubscriptionKind::NewHeads, EthFilter::default());
+        let filter = EthFilter {
+            address: Some(VariadicValue::Single(Data20::new(H160::from_low_u64_be(1)))),
+            ..Default::default()
+        };
+        let (logs_id, mut logs) = registry.subscribe(SubscriptionKind::Logs, filter);
+        let (_, mut pending) = registry.subscribe(
+            SubscriptionKind::NewPendingTransactions,
+            EthFilter::default(),
+        );
+        let (_, mut syncing) = registry.subscribe(SubscriptionKind::Syncing, EthFilter::default());
+        assert_eq!(registry.len(), 4);
+
+        let hash = Data32::new(H256::from_low_u64_be(7));
+        let events = vec![
+            ChainEvent::NewBlock {
+                header: header(1),
+                logs: vec![log(1, 1), log(2, 1)],
+            },
+            ChainEvent::Reorg {
+                removed: vec![log(1, 1), log(2, 1)],
+            },
+            ChainEvent::PendingTransaction(hash.clone()),
+            ChainEvent::Syncing(SyncingResult::Syncing(SyncStatus::default())),
+        ];
+        block_on(registry.run(stream::iter(events)));
+
+        assert_eq!(
+            results(&mut heads),
+            vec![SubscriptionResult::Header(header(1))]
+        );
+        let mut removed = log(1, 1);
+        removed.removed = true;
+        assert_eq!(
+            results(&mut logs),
+            vec![
+                SubscriptionResult::Log(Box::new(log(1, 1))),
+                SubscriptionResult::Log(Box::new(removed)),
+            ]
+        );
+        assert_eq!(
+            results(&mut pending),
+            vec![SubscriptionResult::TransactionHash(hash)]
+        );
+        assert_eq!(
+            results(&mut syncing),
+            vec![SubscriptionResult::Syncing(SyncingResult::Syncing(
+                SyncStatus::default()
+            ))]
+        );
+
+        assert!(registry.unsubscribe(&logs_id));
+        assert!(!registry.unsubscribe(&logs_id));
+        assert_eq!(registry.len(), 3);
+    }
+
+    #[test]
+    fn test_subscription_dropped_receiver() {
+        let registry = SubscriptionRegistry::new();
+        let (_, heads) = registry.subscribe(SubscriptionKind::NewHeads, EthFilter::default());
+        let (_, _syncing) = registry.subscribe(SubscriptionKind::Syncing, EthFilter::default());
+        drop(heads);
+
+        let sent = registry.on_event(&ChainEvent::PendingTransaction(Data32::new(H256::zero())));
+        assert_eq!(sent, 0);
+        assert_eq!(registry.len(), 1);
+    }
+}
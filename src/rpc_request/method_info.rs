@@ -0,0 +1,124 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use crate::impl_for_each_jsonrpc_requests;
+use crate::internals::construct_methods;
+use crate::rpc_request::*;
+
+pub type RpcModules = BTreeMap<String, String>;
+
+/// Metadata of a JSON-RPC method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodInfo {
+    /// JSON-RPC method name
+    pub name: &'static str,
+    /// Namespace, such as `eth` or `net`, `cita` for CITA native methods
+    pub namespace: &'static str,
+    /// The same as `JsonRpcRequest::required_len`
+    pub required_len: usize,
+    /// The same as `JsonRpcRequest::valid_len`
+    pub valid_len: usize,
+    /// The same as `JsonRpcRequest::param_names`
+    pub param_names: &'static [&'static str],
+    pub param_types: &'static [&'static str],
+    pub result_type: &'static str,
+}
+
+impl MethodInfo {
+    /// Find the method by JSON-RPC method name.
+    pub fn find(name: &str) -> Option<&'static MethodInfo> {
+        Self::index(name).map(|index| &METHODS[index])
+    }
+
+    /// The index of the method in `METHODS`, and in every other table generated
+    /// by `impl_for_each_jsonrpc_requests`.
+    pub(crate) fn index(name: &str) -> Option<usize> {
+        static INDEX: OnceLock<HashMap<&'static str, usize>> = OnceLock::new();
+        INDEX
+            .get_or_init(|| {
+                METHODS
+                    .iter()
+                    .enumerate()
+                    .map(|(index, info)| (info.name, index))
+                    .collect()
+            })
+            .get(name)
+            .copied()
+    }
+
+    /// All methods in the namespace.
+    pub fn in_namespace(namespace: &str) -> impl Iterator<Item = &'static MethodInfo> + '_ {
+        METHODS
+            .iter()
+            .filter(move |info| info.namespace == namespace)
+    }
+
+    pub fn is_optional(&self, index: usize) -> bool {
+        index >= self.required_len && index < self.valid_len
+    }
+}
+
+/// The result of `rpc_modules`: every namespace with its version.
+pub fn rpc_modules() -> RpcModules {
+    METHODS
+        .iter()
+        .map(|info| (info.namespace.to_owned(), "1.0".to_owned()))
+        .collect()
+}
+
+impl_for_each_jsonrpc_requests!(construct_methods);
+
+#[cfg(test)]
+mod tests {
+    use super::{rpc_modules, MethodInfo, METHODS};
+    use crate::rpc_request::{eth_estimateGasParams, GetTransactionParams, JsonRpcRequest};
+
+    #[test]
+    fn test_method_info() {
+        let info = MethodInfo::find("eth_estimateGas").unwrap();
+        assert_eq!(info.namespace, "eth");
+        assert_eq!(info.required_len, eth_estimateGasParams::required_len());
+        assert_eq!(info.valid_len, eth_estimateGasParams::valid_len());
+        assert_eq!(info.param_names, &["transaction", "block"]);
+        assert_eq!(info.param_types, &["EthCallRequest", "BlockNumber"]);
+        assert_eq!(info.result_type, "Quantity");
+        assert!(!info.is_optional(0));
+        assert!(info.is_optional(1));
+
+        let info = MethodInfo::find("getTransaction").unwrap();
+        assert_eq!(info.namespace, "cita");
+        assert_eq!(info.required_len, GetTransactionParams::required_len());
+        assert!(info.param_names.is_empty());
+        assert_eq!(info.result_type, "RpcTransaction");
+
+        assert!(MethodInfo::find("notAMethod").is_none());
+        assert!(MethodInfo::in_namespace("net").all(|info| info.name.starts_with("net_")));
+    }
+
+    #[test]
+    fn test_rpc_modules() {
+        let modules = rpc_modules();
+        assert_eq!(
+            modules.keys().map(String::as_str).collect::<Vec<_>>(),
+            vec!["cita", "debug", "eth", "net", "personal", "rpc", "trace", "txpool", "web3"]
+        );
+        assert!(modules.values().all(|version| version == "1.0"));
+        assert!(METHODS.iter().any(|info| info.name == "rpc_modules"));
+        assert_eq!(MethodInfo::find("rpc.discover").unwrap().namespace, "rpc");
+    }
+}
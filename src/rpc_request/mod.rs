@@ -12,20 +12,42 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod method_info;
+mod openrpc;
+mod rawrequest;
 mod request;
 mod rpcrequest;
 #[cfg(test)]
 mod tests;
+mod validation;
 
+pub(crate) use self::method_info::method_schemas;
+pub use self::method_info::{rpc_modules, MethodInfo, RpcModules, METHODS};
+pub use self::openrpc::{
+    hex_schema, object_schema, openrpc_document, ContentDescriptor, OpenRpcComponents,
+    OpenRpcDocument, OpenRpcInfo, OpenRpcMethod, OpenRpcSchema, OPENRPC_VERSION,
+};
+pub use self::rawrequest::{RawRequest, RawRpcRequest};
 pub use self::request::{
+    debug_traceBlockByNumberParams, debug_traceCallParams, debug_traceTransactionParams,
     eth_accountsParams, eth_blockNumberParams, eth_callParams, eth_chainIdParams,
-    eth_estimateGasParams, eth_gasPriceParams, eth_getBalanceParams, eth_getBlockByHashParams,
-    eth_getBlockByNumberParams, eth_getBlockTransactionCountByHashParams,
-    eth_getBlockTransactionCountByNumberParams, eth_getCodeParams, eth_getLogsParams,
-    eth_getStorageAtParams, eth_getTransactionByBlockHashAndIndexParams,
-    eth_getTransactionByBlockNumberAndIndexParams, eth_getTransactionByHashParams,
-    eth_getTransactionCountParams, eth_getTransactionReceiptParams, eth_maxPriorityFeePerGasParams,
-    eth_sendRawTransactionParams, eth_sendTransactionParams, eth_syncingParams, net_versionParams,
+    eth_createAccessListParams, eth_estimateGasParams, eth_feeHistoryParams, eth_gasPriceParams,
+    eth_getBalanceParams, eth_getBlockByHashParams, eth_getBlockByNumberParams,
+    eth_getBlockReceiptsParams, eth_getBlockTransactionCountByHashParams,
+    eth_getBlockTransactionCountByNumberParams, eth_getCodeParams, eth_getFilterChangesParams,
+    eth_getFilterLogsParams, eth_getLogsParams, eth_getProofParams, eth_getStorageAtParams,
+    eth_getTransactionByBlockHashAndIndexParams, eth_getTransactionByBlockNumberAndIndexParams,
+    eth_getTransactionByHashParams, eth_getTransactionCountParams, eth_getTransactionReceiptParams,
+    eth_getUncleByBlockHashAndIndexParams, eth_getUncleByBlockNumberAndIndexParams,
+    eth_getUncleCountByBlockHashParams, eth_getUncleCountByBlockNumberParams,
+    eth_maxPriorityFeePerGasParams, eth_newBlockFilterParams, eth_newFilterParams,
+    eth_newPendingTransactionFilterParams, eth_sendRawTransactionParams, eth_sendTransactionParams,
+    eth_signParams, eth_signTransactionParams, eth_signTypedData_v4Params, eth_subscribeParams,
+    eth_syncingParams, eth_uninstallFilterParams, eth_unsubscribeParams, net_listeningParams,
+    net_peerCountParams, net_versionParams, personal_signParams, rpc_discoverParams,
+    rpc_modulesParams, trace_blockParams, trace_callParams, trace_filterParams,
+    trace_replayTransactionParams, trace_transactionParams, txpool_contentParams,
+    txpool_inspectParams, txpool_statusParams, web3_clientVersionParams, web3_sha3Params,
     BlockNumberParams, CallParams, EstimateQuotaParams, GetAbiParams, GetBalanceParams,
     GetBlockByHashParams, GetBlockByNumberParams, GetBlockHeaderParams, GetCensoredAddrsParams,
     GetCodeParams, GetFilterChangesParams, GetFilterLogsParams, GetLogsParams, GetMetaDataParams,
@@ -36,6 +58,10 @@ pub use self::request::{
     UninstallFilterParams,
 };
 pub use self::request::{
-    Call, JsonRpcRequest, PartialCall, PartialRequest, Request, RequestInfo, ResponseResult,
+    Accounts, BlockTraces, Call, EthLogs, EthReceipts, EthUncle, JsonRpcRequest, LocalizedTraces,
+    Logs, MethodName, PartialCall, PartialRequest, Request, RequestInfo, ResponseResult,
+    RewardPercentiles, StorageKeys, TraceTypes,
 };
 pub use self::rpcrequest::RpcRequest;
+pub(crate) use self::validation::deserialize_present;
+pub use self::validation::ValidationMode;
@@ -0,0 +1,370 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenRPC document (https://spec.open-rpc.org) of all JSON-RPC methods.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::rpc_request::{method_schemas, MethodInfo, METHODS};
+
+pub const OPENRPC_VERSION: &str = "1.2.6";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenRpcDocument {
+    pub openrpc: String,
+    pub info: OpenRpcInfo,
+    pub methods: Vec<OpenRpcMethod>,
+    pub components: OpenRpcComponents,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenRpcInfo {
+    pub title: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenRpcMethod {
+    pub name: String,
+    pub params: Vec<ContentDescriptor>,
+    pub result: ContentDescriptor,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub required: bool,
+    pub schema: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct OpenRpcComponents {
+    pub schemas: BTreeMap<String, Value>,
+}
+
+/// A type described in the OpenRPC document, the schema is implemented next
+/// to the type.
+pub trait OpenRpcSchema {
+    /// The schema of the type, a named schema is added to the components and
+    /// referenced.
+    fn schema(components: &mut OpenRpcComponents) -> Value;
+}
+
+impl OpenRpcComponents {
+    /// The schema of `T`.
+    pub fn schema<T: OpenRpcSchema + ?Sized>(&mut self) -> Value {
+        T::schema(self)
+    }
+
+    /// Reference the named schema, which is built and added to the components
+    /// the first time it is referenced.
+    pub fn reference<F>(&mut self, name: &str, build: F) -> Value
+    where
+        F: FnOnce(&mut Self) -> Value,
+    {
+        if !self.schemas.contains_key(name) {
+            // Reserved before building, so a recursive type ends here.
+            self.schemas.insert(name.to_owned(), Value::Null);
+            let schema = build(self);
+            self.schemas.insert(name.to_owned(), schema);
+        }
+        json!({ "$ref": format!("#/components/schemas/{}", name) })
+    }
+}
+
+/// A string of the hex pattern.
+pub fn hex_schema(name: &str, pattern: &str) -> Value {
+    json!({
+        "title": name,
+        "type": "string",
+        "pattern": pattern,
+    })
+}
+
+/// An object with the properties, which are optional unless in `required`.
+pub fn object_schema(name: &str, properties: Vec<(&str, Value)>, required: &[&str]) -> Value {
+    let properties: serde_json::Map<String, Value> = properties
+        .into_iter()
+        .map(|(key, schema)| (key.to_owned(), schema))
+        .collect();
+    json!({
+        "title": name,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+impl<T: OpenRpcSchema> OpenRpcSchema for Vec<T> {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        json!({ "type": "array", "items": T::schema(components) })
+    }
+}
+
+impl<T: OpenRpcSchema> OpenRpcSchema for Option<T> {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        json!({ "oneOf": [{ "type": "null" }, T::schema(components)] })
+    }
+}
+
+impl<T: OpenRpcSchema> OpenRpcSchema for BTreeMap<String, T> {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema(components) })
+    }
+}
+
+impl OpenRpcSchema for Value {
+    fn schema(_: &mut OpenRpcComponents) -> Value {
+        json!({})
+    }
+}
+
+impl OpenRpcSchema for String {
+    fn schema(_: &mut OpenRpcComponents) -> Value {
+        json!({ "type": "string" })
+    }
+}
+
+impl OpenRpcSchema for bool {
+    fn schema(_: &mut OpenRpcComponents) -> Value {
+        json!({ "type": "boolean" })
+    }
+}
+
+impl OpenRpcSchema for f64 {
+    fn schema(_: &mut OpenRpcComponents) -> Value {
+        json!({ "type": "number" })
+    }
+}
+
+macro_rules! impl_schema_for_uint {
+    ($($uint:ty),+) => {
+        $(
+            impl OpenRpcSchema for $uint {
+                fn schema(_: &mut OpenRpcComponents) -> Value {
+                    json!({ "type": "integer", "minimum": 0 })
+                }
+            }
+        )+
+    };
+}
+
+impl_schema_for_uint!(u32, u64, usize);
+
+impl OpenRpcSchema for OpenRpcDocument {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("OpenRpcDocument", |components| {
+            object_schema(
+                "OpenRpcDocument",
+                vec![
+                    ("openrpc", components.schema::<String>()),
+                    ("info", components.schema::<OpenRpcInfo>()),
+                    ("methods", components.schema::<Vec<OpenRpcMethod>>()),
+                    ("components", components.schema::<OpenRpcComponents>()),
+                ],
+                &["openrpc", "info", "methods", "components"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for OpenRpcInfo {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("OpenRpcInfo", |components| {
+            object_schema(
+                "OpenRpcInfo",
+                vec![
+                    ("title", components.schema::<String>()),
+                    ("version", components.schema::<String>()),
+                ],
+                &["title", "version"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for OpenRpcMethod {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("OpenRpcMethod", |components| {
+            object_schema(
+                "OpenRpcMethod",
+                vec![
+                    ("name", components.schema::<String>()),
+                    ("params", components.schema::<Vec<ContentDescriptor>>()),
+                    ("result", components.schema::<ContentDescriptor>()),
+                ],
+                &["name", "params", "result"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for ContentDescriptor {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("ContentDescriptor", |components| {
+            object_schema(
+                "ContentDescriptor",
+                vec![
+                    ("name", components.schema::<String>()),
+                    ("required", components.schema::<bool>()),
+                    // Any JSON schema.
+                    ("schema", components.schema::<Value>()),
+                ],
+                &["name", "schema"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for OpenRpcComponents {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("OpenRpcComponents", |components| {
+            object_schema(
+                "OpenRpcComponents",
+                vec![("schemas", components.schema::<BTreeMap<String, Value>>())],
+                &["schemas"],
+            )
+        })
+    }
+}
+
+impl OpenRpcMethod {
+    fn from_info(info: &MethodInfo, params: Vec<Value>, result: Value) -> Self {
+        let params = params
+            .into_iter()
+            .enumerate()
+            .map(|(index, schema)| ContentDescriptor {
+                name: info
+                    .param_names
+                    .get(index)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("p{}", index)),
+                required: !info.is_optional(index),
+                schema,
+            })
+            .collect();
+        OpenRpcMethod {
+            name: info.name.to_owned(),
+            params,
+            result: ContentDescriptor {
+                name: info.result_type.to_owned(),
+                required: false,
+                schema: result,
+            },
+        }
+    }
+}
+
+/// Generate the OpenRPC document from `METHODS`.
+pub fn openrpc_document() -> OpenRpcDocument {
+    let mut components = OpenRpcComponents::default();
+    let methods = METHODS
+        .iter()
+        .zip(method_schemas(&mut components))
+        .map(|(info, (params, result))| OpenRpcMethod::from_info(info, params, result))
+        .collect();
+    OpenRpcDocument {
+        openrpc: OPENRPC_VERSION.to_owned(),
+        info: OpenRpcInfo {
+            title: env!("CARGO_PKG_NAME").to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        methods,
+        components,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{openrpc_document, OPENRPC_VERSION};
+    use crate::rpc_request::METHODS;
+    use serde_json::{self, Value};
+
+    // Collect all `$ref` in the value.
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    refs.push(r.clone());
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(vec) => vec.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_openrpc_document() {
+        let doc = openrpc_document();
+        assert_eq!(doc.openrpc, OPENRPC_VERSION);
+        assert_eq!(doc.methods.len(), METHODS.len());
+
+        let method = doc
+            .methods
+            .iter()
+            .find(|method| method.name == "eth_estimateGas")
+            .unwrap();
+        assert_eq!(method.params.len(), 2);
+        assert_eq!(method.params[0].name, "transaction");
+        assert!(method.params[0].required);
+        assert_eq!(
+            method.params[0].schema,
+            json!({"$ref": "#/components/schemas/EthCallRequest"})
+        );
+        assert_eq!(method.params[1].name, "block");
+        assert!(!method.params[1].required);
+        assert_eq!(method.result.name, "Quantity");
+
+        let method = doc
+            .methods
+            .iter()
+            .find(|method| method.name == "getBlockByNumber")
+            .unwrap();
+        assert_eq!(method.params[0].name, "p0");
+        assert_eq!(
+            method.result.schema,
+            json!({"$ref": "#/components/schemas/Block"})
+        );
+        assert_eq!(
+            doc.components.schemas["Block"]["required"],
+            json!(["version", "hash", "header", "body"])
+        );
+
+        assert!(doc
+            .methods
+            .iter()
+            .any(|method| method.name == "rpc.discover"));
+    }
+
+    #[test]
+    fn test_openrpc_refs_resolved() {
+        let doc = serde_json::to_value(openrpc_document()).unwrap();
+        let mut refs = Vec::new();
+        collect_refs(&doc, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.trim_start_matches("#/components/schemas/");
+            let schema = doc["components"]["schemas"].get(name);
+            assert!(
+                schema.is_some_and(|schema| !schema.is_null()),
+                "unresolved {}",
+                r
+            );
+        }
+    }
+}
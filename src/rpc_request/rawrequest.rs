@@ -0,0 +1,117 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{self, value::RawValue};
+
+use super::request::RequestInfo;
+use super::validation::{deserialize_present, Method, ValidationMode};
+use crate::rpc_types::{Id, Version};
+use crate::Error;
+
+/// JSON-RPC request borrowed from the body.
+///
+/// The params are kept as raw JSON, and decoded straight into the params type
+/// of the method when the request is completed.
+#[derive(Deserialize, Debug)]
+pub struct RawRequest<'a> {
+    #[serde(default)]
+    pub jsonrpc: Option<Version>,
+    /// `None` if the request has no `id` member.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub id: Option<Id>,
+    #[serde(default, borrow, deserialize_with = "deserialize_method")]
+    pub method: Option<Cow<'a, str>>,
+    #[serde(default, borrow)]
+    pub params: Option<&'a RawValue>,
+}
+
+// `Option<Cow<str>>` is never borrowed by serde, so borrow it through a newtype.
+fn deserialize_method<'de, D>(deserializer: D) -> Result<Option<Cow<'de, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Method>::deserialize(deserializer).map(|method| method.map(|Method(name)| name))
+}
+
+impl<'a> RawRequest<'a> {
+    pub fn get_info(&self) -> RequestInfo {
+        RequestInfo::new(self.jsonrpc.clone(), self.id.clone().unwrap_or_default())
+    }
+
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// Represents jsonrpc request borrowed from the body.
+#[derive(Debug)]
+pub enum RawRpcRequest<'a> {
+    /// Single request
+    Single(RawRequest<'a>),
+    /// Batch of requests, each one is parsed on its own.
+    Batch(Vec<&'a RawValue>),
+}
+
+impl<'a> RawRpcRequest<'a> {
+    /// Parse the body in one pass, a batch or a single request is decided by the
+    /// first non-whitespace byte.
+    pub fn parse(body: &'a str, mode: ValidationMode) -> Result<Self, Error> {
+        let first = body.bytes().find(|b| !b.is_ascii_whitespace());
+        if first == Some(b'[') {
+            serde_json::from_str(body)
+                .map(RawRpcRequest::Batch)
+                .map_err(|err| Error::parse_error_with_message(err.to_string()))
+        } else {
+            mode.parse_request(body).map(RawRpcRequest::Single)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single() {
+        let body =
+            r#" {"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": [{}, "latest"]}"#;
+        match RawRpcRequest::parse(body, ValidationMode::Lenient).unwrap() {
+            RawRpcRequest::Single(req) => {
+                assert_eq!(
+                    req.get_info(),
+                    RequestInfo::new(Some(Version::V2), Id::Num(1))
+                );
+                assert!(matches!(req.method, Some(Cow::Borrowed("eth_call"))));
+                assert_eq!(req.params.unwrap().get(), r#"[{}, "latest"]"#);
+            }
+            RawRpcRequest::Batch(_) => panic!("should be a single request"),
+        }
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let body = "\n [{\"id\": 1, \"method\": \"eth_blockNumber\"}, 1]";
+        match RawRpcRequest::parse(body, ValidationMode::Lenient).unwrap() {
+            RawRpcRequest::Batch(entries) => {
+                assert_eq!(entries.len(), 2);
+                assert_eq!(entries[1].get(), "1");
+            }
+            RawRpcRequest::Single(_) => panic!("should be a batch request"),
+        }
+        assert!(RawRpcRequest::parse("[{}", ValidationMode::Lenient).is_err());
+    }
+}
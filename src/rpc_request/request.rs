@@ -14,21 +14,35 @@
 #![allow(non_camel_case_types)]
 
 use crate::internals::construct_params;
+use crate::rpc_request::{OpenRpcComponents, OpenRpcDocument, OpenRpcSchema, RpcModules};
 use crate::rpc_types::ethereum_types::{
-    EthBlock, EthCallRequest, EthFilter, EthLog, EthReceipt, EthRpcTransaction,
-    EthTransactionRequest,
+    AccessListWithGasUsed, BlockOverrides, BlockTraceResult, DebugTrace, Eip712TypedData,
+    EthAccountProof, EthBlock, EthCallRequest, EthFilter, EthFilterChanges, EthLog, EthReceipt,
+    EthRpcTransaction, EthTransactionRequest, FeeHistory, StateOverride, SubscriptionId,
+    SubscriptionKind, TracerConfig, TxpoolContent, TxpoolInspect, TxpoolStatus,
 };
+use crate::rpc_types::parity_types::trace::{LocalizedTrace, TraceFilter, TraceResults, TraceType};
 use crate::rpc_types::{
     Block, BlockNumber, Boolean, CallRequest, CallResult, CensorAddrs, Data, Data20, Data32,
     Filter, FilterChanges, Id, Integer, LicenseInfo, Log, MetaData, OneItemTupleTrick, PeersInfo,
     PoolTxNum, Quantity, Receipt, RpcTransaction, SoftwareVersion, TxResponse, Version,
 };
 /// JSON-RPC Request.
+use serde::{Serialize, Serializer};
 use serde_json;
 
 pub type Logs = Vec<Log>;
 pub type EthLogs = Vec<EthLog>;
+pub type EthReceipts = Vec<EthReceipt>;
 pub type Accounts = Vec<Data20>;
+pub type RewardPercentiles = Vec<f64>;
+pub type StorageKeys = Vec<Data32>;
+/// Uncle block, always `null` for CITA.
+pub type EthUncle = Option<EthBlock>;
+pub type BlockTraces = Vec<BlockTraceResult>;
+/// Parity traces, `null` if the block or the transaction is not found.
+pub type LocalizedTraces = Option<Vec<LocalizedTrace>>;
+pub type TraceTypes = Vec<TraceType>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct RequestInfo {
@@ -84,13 +98,50 @@ impl Into<String> for Request {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct PartialRequest {
     pub jsonrpc: Option<Version>,
+    #[serde(default)]
     pub id: Id,
     /// Contain method and params.
     #[serde(flatten)]
     pub call: Option<PartialCall>,
+    /// The method as sent, to tell a request without method from a request
+    /// of an unknown method, for both of them `call` is `None`.
+    #[serde(flatten)]
+    pub method: MethodName,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MethodName {
+    #[serde(rename = "method", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Serialize for PartialRequest {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct FlatRequest<'a> {
+            jsonrpc: &'a Option<Version>,
+            id: &'a Id,
+            #[serde(flatten)]
+            call: &'a Option<PartialCall>,
+            // The method is in the call if there is one.
+            #[serde(flatten, skip_serializing_if = "Option::is_none")]
+            method: Option<&'a MethodName>,
+        }
+
+        FlatRequest {
+            jsonrpc: &self.jsonrpc,
+            id: &self.id,
+            call: &self.call,
+            method: self.call.as_ref().map_or(Some(&self.method), |_| None),
+        }
+        .serialize(serializer)
+    }
 }
 
 impl PartialRequest {
@@ -100,13 +151,13 @@ impl PartialRequest {
 }
 
 macro_rules! define_call {
-    ($( ($enum_name:ident, $params_name:ident: $params_list:expr, $result_type:ident) ),+ ,) => {
-        define_call!($( ($enum_name, $params_name: $params_list, $result_type) ),+);
+    ($( ($(#[$attr:meta])* $enum_name:ident, $params_name:ident: $params_list:tt, $result_type:ident) ),+ ,) => {
+        define_call!($( ($(#[$attr])* $enum_name, $params_name: $params_list, $result_type) ),+);
     };
-    ($( ($enum_name:ident, $params_name:ident: $params_list:expr, $result_type:ident) ),+ ) => {
+    ($( ($(#[$attr:meta])* $enum_name:ident, $params_name:ident: $params_list:tt, $result_type:ident) ),+ ) => {
 
         $(
-            construct_params!($params_name: $params_list, $result_type);
+            construct_params!($(#[$attr])* $params_name: $params_list, $result_type);
         )+
 
 
@@ -131,6 +182,7 @@ macro_rules! define_call {
         #[serde(tag = "method", rename_all = "camelCase")]
         pub enum Call {
             $(
+                $(#[$attr])*
                 $enum_name { params: $params_name},
             )+
         }
@@ -139,6 +191,7 @@ macro_rules! define_call {
         #[serde(tag = "method", rename_all = "camelCase")]
         pub enum PartialCall {
             $(
+                $(#[$attr])*
                 $enum_name {
                     params: Option<serde_json::Value>
                 },
@@ -200,7 +253,18 @@ pub trait JsonRpcRequest {
     fn required_len() -> usize;
     fn valid_len() -> usize;
     fn method_name(&self) -> &'static str;
+    /// The names of params, empty if the method only accepts positional params.
+    fn param_names() -> &'static [&'static str];
     fn value_vec(self) -> Vec<serde_json::Value>;
+    /// The schemas of the params in the OpenRPC document.
+    fn param_schemas(components: &mut OpenRpcComponents) -> Vec<serde_json::Value>;
+    /// Decode the params from positional raw values, returns the index of the
+    /// param which failed to decode on error. A `None` is an omitted param.
+    fn from_raw_values(
+        values: Vec<Option<&serde_json::value::RawValue>>,
+    ) -> Result<Self, (usize, serde_json::Error)>
+    where
+        Self: Sized;
 }
 
 // Q. How to add a JSON-RPC method?
@@ -215,9 +279,17 @@ pub trait JsonRpcRequest {
 //    - The 2st item is params type name and it's structure.
 //      The params type has some methods, such as `new()` and `method_name()`.
 //      More details can found in the definition of `construct_params`.
+//      The params can be named as `[address: Data20, block: BlockNumber]`,
+//      then the method also accepts params by-object.
 //
 //    - The 3rd item is the type of result of Response object on success.
 //
+//    The JSON-RPC method name can be renamed by `#[serde(rename = "...")]`
+//    before the enum name, as `rpc.discover`.
+//
+//    The handler method in `EthRpcHandler` is named as the snake case of the
+//    enum name, e.g. `eth_getBalance` is handled by `eth_get_balance`.
+//
 //  Second, implement `TryInto<ProtoRequest>` for the new params type.
 //
 //  DONE!
@@ -271,33 +343,124 @@ macro_rules! impl_for_each_jsonrpc_requests {
             // ethereum jsonrpc
             (eth_blockNumber, eth_blockNumberParams: [], Quantity),
             (eth_chainId, eth_chainIdParams: [], Quantity),
-            (eth_getBlockByHash, eth_getBlockByHashParams: [Data32, Boolean], EthBlock),
-            (eth_getBlockByNumber, eth_getBlockByNumberParams: [BlockNumber, Boolean], EthBlock),
-            (eth_getTransactionByHash, eth_getTransactionByHashParams: [Data32], EthRpcTransaction),
-            (eth_getTransactionByBlockHashAndIndex, eth_getTransactionByBlockHashAndIndexParams: [Data32, Integer], EthRpcTransaction),
-            (eth_getTransactionByBlockNumberAndIndex, eth_getTransactionByBlockNumberAndIndexParams: [BlockNumber, Integer], EthRpcTransaction),
-            (eth_getBlockTransactionCountByHash, eth_getBlockTransactionCountByHashParams: [Data32], Integer),
-            (eth_getBlockTransactionCountByNumber, eth_getBlockTransactionCountByNumberParams: [BlockNumber], Integer),
-            (eth_getTransactionReceipt, eth_getTransactionReceiptParams: [Data32], EthReceipt),
-            (eth_getBalance, eth_getBalanceParams: [Data20, BlockNumber], Quantity),
+            (eth_getBlockByHash, eth_getBlockByHashParams: [blockHash: Data32, hydratedTransactions: Boolean], EthBlock),
+            (eth_getBlockByNumber, eth_getBlockByNumberParams: [block: BlockNumber, hydratedTransactions: Boolean], EthBlock),
+            (eth_getTransactionByHash, eth_getTransactionByHashParams: [transactionHash: Data32], EthRpcTransaction),
+            (eth_getTransactionByBlockHashAndIndex, eth_getTransactionByBlockHashAndIndexParams: [blockHash: Data32, transactionIndex: Integer], EthRpcTransaction),
+            (eth_getTransactionByBlockNumberAndIndex, eth_getTransactionByBlockNumberAndIndexParams: [block: BlockNumber, transactionIndex: Integer], EthRpcTransaction),
+            (eth_getBlockTransactionCountByHash, eth_getBlockTransactionCountByHashParams: [blockHash: Data32], Integer),
+            (eth_getBlockTransactionCountByNumber, eth_getBlockTransactionCountByNumberParams: [block: BlockNumber], Integer),
+            (eth_getTransactionReceipt, eth_getTransactionReceiptParams: [transactionHash: Data32], EthReceipt),
+            (eth_getBlockReceipts, eth_getBlockReceiptsParams: [block: BlockNumber], EthReceipts),
+            (eth_getUncleByBlockHashAndIndex, eth_getUncleByBlockHashAndIndexParams: [blockHash: Data32, uncleIndex: Quantity], EthUncle),
+            (eth_getUncleByBlockNumberAndIndex, eth_getUncleByBlockNumberAndIndexParams: [block: BlockNumber, uncleIndex: Quantity], EthUncle),
+            (eth_getUncleCountByBlockHash, eth_getUncleCountByBlockHashParams: [blockHash: Data32], Integer),
+            (eth_getUncleCountByBlockNumber, eth_getUncleCountByBlockNumberParams: [block: BlockNumber], Integer),
+            (eth_getBalance, eth_getBalanceParams: [address: Data20, block: BlockNumber], Quantity),
             (eth_syncing, eth_syncingParams: [], Boolean),
-            (eth_getStorageAt, eth_getStorageAtParams: [Data20, Quantity, BlockNumber], Data),
-            (eth_getCode, eth_getCodeParams: [Data20, BlockNumber], Data),
-            (eth_getTransactionCount, eth_getTransactionCountParams: [Data20, BlockNumber], Quantity),
-            (eth_getLogs, eth_getLogsParams: [EthFilter], EthLogs),
-            (eth_call, eth_callParams: [EthCallRequest, BlockNumber], Data),
+            (eth_getProof, eth_getProofParams: [
+                address: Data20,
+                storageKeys: StorageKeys,
+                block: BlockNumber
+            ], EthAccountProof),
+            (eth_getStorageAt, eth_getStorageAtParams: [address: Data20, position: Quantity, block: BlockNumber], Data),
+            (eth_getCode, eth_getCodeParams: [address: Data20, block: BlockNumber], Data),
+            (eth_getTransactionCount, eth_getTransactionCountParams: [address: Data20, block: BlockNumber], Quantity),
+            (eth_getLogs, eth_getLogsParams: [filter: EthFilter], EthLogs),
+            (eth_newFilter, eth_newFilterParams: [filter: EthFilter], Quantity),
+            (eth_newBlockFilter, eth_newBlockFilterParams: [], Quantity),
+            (eth_newPendingTransactionFilter, eth_newPendingTransactionFilterParams: [], Quantity),
+            (eth_getFilterChanges, eth_getFilterChangesParams: [filterId: Quantity], EthFilterChanges),
+            (eth_getFilterLogs, eth_getFilterLogsParams: [filterId: Quantity], EthLogs),
+            (eth_uninstallFilter, eth_uninstallFilterParams: [filterId: Quantity], Boolean),
+            (eth_subscribe, eth_subscribeParams: [
+                kind: SubscriptionKind,
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                filter: Option<EthFilter>
+            ], SubscriptionId),
+            (eth_unsubscribe, eth_unsubscribeParams: [subscriptionId: SubscriptionId], Boolean),
+            (eth_call, eth_callParams: [
+                transaction: EthCallRequest,
+                block: BlockNumber,
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                stateOverride: Option<StateOverride>,
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                blockOverrides: Option<BlockOverrides>,
+            ], Data),
             (eth_estimateGas, eth_estimateGasParams: [
-                EthCallRequest,
+                transaction: EthCallRequest,
                 #[serde(default)]
-                BlockNumber
+                block: BlockNumber
             ], Quantity),
+            (eth_createAccessList, eth_createAccessListParams: [
+                transaction: EthCallRequest,
+                #[serde(default)]
+                block: BlockNumber
+            ], AccessListWithGasUsed),
             (eth_gasPrice, eth_gasPriceParams: [], Quantity),
             (eth_maxPriorityFeePerGas, eth_maxPriorityFeePerGasParams: [], Quantity),
-            (eth_sendTransaction, eth_sendTransactionParams: [EthTransactionRequest], Data32),
-            (eth_sendRawTransaction, eth_sendRawTransactionParams: [Data], Data32),
+            (eth_feeHistory, eth_feeHistoryParams: [
+                blockCount: Quantity,
+                newestBlock: BlockNumber,
+                #[serde(default)]
+                rewardPercentiles: RewardPercentiles
+            ], FeeHistory),
+            (eth_sendTransaction, eth_sendTransactionParams: [transaction: EthTransactionRequest], Data32),
+            (eth_sendRawTransaction, eth_sendRawTransactionParams: [transaction: Data], Data32),
             (eth_accounts, eth_accountsParams: [], Accounts),
+            (eth_sign, eth_signParams: [address: Data20, message: Data], Data),
+            (eth_signTransaction, eth_signTransactionParams: [transaction: EthTransactionRequest], Data),
+            (eth_signTypedData_v4, eth_signTypedData_v4Params: [address: Data20, typedData: Eip712TypedData], Data),
+            (personal_sign, personal_signParams: [
+                message: Data,
+                address: Data20,
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                password: Option<String>
+            ], Data),
             // net jsonrpc
             (net_version, net_versionParams: [], Integer),
+            (net_listening, net_listeningParams: [], Boolean),
+            (net_peerCount, net_peerCountParams: [], Quantity),
+            (web3_clientVersion, web3_clientVersionParams: [], String),
+            (web3_sha3, web3_sha3Params: [data: Data], Data32),
+            // debug jsonrpc
+            (debug_traceTransaction, debug_traceTransactionParams: [
+                hash: Data32,
+                #[serde(default)]
+                config: TracerConfig
+            ], DebugTrace),
+            (debug_traceCall, debug_traceCallParams: [
+                call: EthCallRequest,
+                block: BlockNumber,
+                #[serde(default)]
+                config: TracerConfig
+            ], DebugTrace),
+            (debug_traceBlockByNumber, debug_traceBlockByNumberParams: [
+                block: BlockNumber,
+                #[serde(default)]
+                config: TracerConfig
+            ], BlockTraces),
+            // txpool jsonrpc
+            (txpool_content, txpool_contentParams: [], TxpoolContent),
+            (txpool_inspect, txpool_inspectParams: [], TxpoolInspect),
+            (txpool_status, txpool_statusParams: [], TxpoolStatus),
+            // trace jsonrpc
+            (trace_block, trace_blockParams: [block: BlockNumber], LocalizedTraces),
+            (trace_transaction, trace_transactionParams: [hash: Data32], LocalizedTraces),
+            (trace_filter, trace_filterParams: [filter: TraceFilter], LocalizedTraces),
+            (trace_call, trace_callParams: [
+                call: EthCallRequest,
+                traceTypes: TraceTypes,
+                #[serde(default)]
+                block: BlockNumber
+            ], TraceResults),
+            (trace_replayTransaction, trace_replayTransactionParams: [
+                hash: Data32,
+                traceTypes: TraceTypes
+            ], TraceResults),
+            // rpc jsonrpc
+            (rpc_modules, rpc_modulesParams: [], RpcModules),
+            (#[serde(rename = "rpc.discover")] rpc_discover, rpc_discoverParams: [], OpenRpcDocument),
         );
     };
 }
@@ -14,7 +14,7 @@
 
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use serde_json;
+use serde_json::{self, value::RawValue};
 
 use super::request::PartialRequest;
 
@@ -40,14 +40,23 @@ impl Serialize for RpcRequest {
 }
 
 impl<'a> Deserialize<'a> for RpcRequest {
+    /// Parse through the raw JSON, a batch or a single request is decided by
+    /// the first byte, so the requests are decoded from the text and the ids
+    /// are kept exactly as received.
+    ///
+    /// The raw JSON is owned, so it works with the readers and `Value` which
+    /// can not lend the text. It is only supported by the deserializers of
+    /// `serde_json`, the others fail with an error.
     fn deserialize<D>(deserializer: D) -> Result<RpcRequest, D::Error>
     where
         D: Deserializer<'a>,
     {
-        let v: serde_json::Value = Deserialize::deserialize(deserializer)?;
-        serde_json::from_value(v.clone())
-            .map(RpcRequest::Batch)
-            .or_else(|_| serde_json::from_value(v).map(RpcRequest::Single))
-            .map_err(|_| D::Error::custom("parse rpcrequest failed")) // unreachable, but types must match
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        if raw.get().starts_with('[') {
+            serde_json::from_str(raw.get()).map(RpcRequest::Batch)
+        } else {
+            serde_json::from_str(raw.get()).map(RpcRequest::Single)
+        }
+        .map_err(D::Error::custom)
     }
 }
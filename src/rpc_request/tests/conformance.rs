@@ -0,0 +1,371 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The examples of the JSON-RPC 2.0 specification (https://www.jsonrpc.org/specification#examples),
+// with `subtract`, `sum` and `update` replaced by the methods of this crate.
+
+use futures::executor::block_on;
+use serde_json::{self, Value};
+
+use crate::rpc_complete::complete::Complete;
+use crate::rpc_handler::{process_batch, BatchConfig, EthRpcHandler};
+use crate::rpc_request::{
+    eth_blockNumberParams, eth_chainIdParams, eth_getBalanceParams, PartialRequest, RpcRequest,
+    ValidationMode,
+};
+use crate::rpc_response::{Output, RpcResponse};
+use crate::rpc_types::{BlockNumber, Id, Quantity};
+use crate::{Error, ErrorCode};
+
+struct Conformance;
+
+#[async_trait::async_trait]
+impl EthRpcHandler for Conformance {
+    async fn eth_block_number(&self, _params: eth_blockNumberParams) -> Result<Quantity, Error> {
+        Ok(Quantity::from(19u64))
+    }
+
+    async fn eth_chain_id(&self, _params: eth_chainIdParams) -> Result<Quantity, Error> {
+        Ok(Quantity::from(1u64))
+    }
+
+    async fn eth_get_balance(&self, params: eth_getBalanceParams) -> Result<Quantity, Error> {
+        match params.1 {
+            BlockNumber::Height(_) => Ok(Quantity::from(42u64)),
+            _ => Ok(Quantity::from(23u64)),
+        }
+    }
+}
+
+fn process(body: &str) -> Option<Value> {
+    let config = BatchConfig::default().set_validation(ValidationMode::Strict);
+    block_on(process_batch(&Conformance, body, &config))
+        .map(|resp| serde_json::to_value(resp).unwrap())
+}
+
+fn single(body: &str) -> RpcRequest {
+    serde_json::from_str::<RpcRequest>(body).unwrap()
+}
+
+#[test]
+fn rpc_call_with_positional_params() {
+    let body = r#"{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "0x1"], "id": 1}"#;
+    assert_eq!(
+        process(body).unwrap(),
+        json!({"jsonrpc": "2.0", "result": "0x2a", "id": 1})
+    );
+
+    let body = r#"{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "latest"], "id": 2}"#;
+    assert_eq!(
+        process(body).unwrap(),
+        json!({"jsonrpc": "2.0", "result": "0x17", "id": 2})
+    );
+
+    match single(body) {
+        RpcRequest::Single(req) => {
+            let req = req.complete().unwrap();
+            assert_eq!(req.id, Id::Num(2));
+            assert_eq!(req.get_method(), "eth_getBalance");
+        }
+        RpcRequest::Batch(_) => panic!("should be a single request"),
+    }
+}
+
+#[test]
+fn rpc_call_with_named_params() {
+    let positional = r#"{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "0x1"], "id": 3}"#;
+    let named = r#"{"jsonrpc": "2.0", "method": "eth_getBalance", "params": {"address": "0x0000000000000000000000000000000000000001", "block": "0x1"}, "id": 3}"#;
+    assert_eq!(
+        process(named).unwrap(),
+        json!({"jsonrpc": "2.0", "result": "0x2a", "id": 3})
+    );
+
+    match (single(positional), single(named)) {
+        (RpcRequest::Single(positional), RpcRequest::Single(named)) => {
+            assert_eq!(named.complete().unwrap(), positional.complete().unwrap());
+        }
+        _ => panic!("should be single requests"),
+    }
+}
+
+#[test]
+fn notification() {
+    assert_eq!(
+        process(r#"{"jsonrpc": "2.0", "method": "eth_blockNumber", "params": []}"#),
+        None
+    );
+    assert_eq!(
+        process(r#"{"jsonrpc": "2.0", "method": "eth_chainId"}"#),
+        None
+    );
+
+    match single(r#"{"jsonrpc": "2.0", "method": "eth_blockNumber", "params": []}"#) {
+        RpcRequest::Single(req) => {
+            assert_eq!(req.complete().unwrap().id, Id::Null);
+        }
+        RpcRequest::Batch(_) => panic!("should be a single request"),
+    }
+}
+
+#[test]
+fn rpc_call_of_non_existent_method() {
+    let body = r#"{"jsonrpc": "2.0", "method": "foobar", "id": "1"}"#;
+    assert_eq!(
+        process(body).unwrap(),
+        json!({"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": "1"})
+    );
+
+    match single(body) {
+        RpcRequest::Single(req) => {
+            assert_eq!(req.complete().unwrap_err(), Error::method_not_found());
+        }
+        RpcRequest::Batch(_) => panic!("should be a single request"),
+    }
+}
+
+#[test]
+fn rpc_call_with_invalid_json() {
+    let body = r#"{"jsonrpc": "2.0", "method": "foobar, "params": "bar", "baz]"#;
+    let resp = process(body).unwrap();
+    assert_eq!(resp["jsonrpc"], json!("2.0"));
+    assert_eq!(resp["error"]["code"], json!(-32700));
+    assert_eq!(resp["id"], Value::Null);
+
+    assert!(serde_json::from_str::<RpcRequest>(body).is_err());
+}
+
+#[test]
+fn rpc_call_with_invalid_request_object() {
+    let body = r#"{"jsonrpc": "2.0", "method": 1, "params": "bar"}"#;
+    let resp = process(body).unwrap();
+    assert_eq!(resp["jsonrpc"], json!("2.0"));
+    assert_eq!(resp["error"]["code"], json!(-32600));
+    assert_eq!(resp["id"], Value::Null);
+}
+
+#[test]
+fn rpc_call_batch_with_invalid_json() {
+    let body = r#"[
+        {"jsonrpc": "2.0", "method": "eth_blockNumber", "params": [], "id": "1"},
+        {"jsonrpc": "2.0", "method"
+    ]"#;
+    let resp = process(body).unwrap();
+    assert!(resp.is_object());
+    assert_eq!(resp["error"]["code"], json!(-32700));
+    assert_eq!(resp["id"], Value::Null);
+
+    assert!(serde_json::from_str::<RpcRequest>(body).is_err());
+}
+
+#[test]
+fn rpc_call_with_empty_array() {
+    assert_eq!(
+        process("[]").unwrap(),
+        json!({"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid request"}, "id": null})
+    );
+}
+
+#[test]
+fn rpc_call_with_invalid_batch() {
+    let resp = process("[1]").unwrap();
+    let outputs = resp.as_array().unwrap();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0]["jsonrpc"], json!("2.0"));
+    assert_eq!(outputs[0]["error"]["code"], json!(-32600));
+    assert_eq!(outputs[0]["id"], Value::Null);
+
+    let resp = process("[1,2,3]").unwrap();
+    let outputs = resp.as_array().unwrap();
+    assert_eq!(outputs.len(), 3);
+    for output in outputs {
+        assert_eq!(output["jsonrpc"], json!("2.0"));
+        assert_eq!(output["error"]["code"], json!(-32600));
+        assert_eq!(output["id"], Value::Null);
+    }
+}
+
+#[test]
+fn rpc_call_batch() {
+    let body = r#"[
+        {"jsonrpc": "2.0", "method": "eth_blockNumber", "id": "1"},
+        {"jsonrpc": "2.0", "method": "eth_chainId", "params": []},
+        {"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "0x1"], "id": "2"},
+        {"foo": "boo"},
+        {"jsonrpc": "2.0", "method": "foo.get", "params": {"name": "myself"}, "id": "5"},
+        {"jsonrpc": "2.0", "method": "eth_chainId", "id": "9"}
+    ]"#;
+    let resp = process(body).unwrap();
+    let outputs = resp.as_array().unwrap();
+    assert_eq!(outputs.len(), 5);
+    assert_eq!(
+        outputs[0],
+        json!({"jsonrpc": "2.0", "result": "0x13", "id": "1"})
+    );
+    assert_eq!(
+        outputs[1],
+        json!({"jsonrpc": "2.0", "result": "0x2a", "id": "2"})
+    );
+    assert_eq!(outputs[2]["error"]["code"], json!(-32600));
+    assert_eq!(outputs[2]["id"], Value::Null);
+    assert_eq!(
+        outputs[3],
+        json!({"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": "5"})
+    );
+    assert_eq!(
+        outputs[4],
+        json!({"jsonrpc": "2.0", "result": "0x1", "id": "9"})
+    );
+
+    let body = r#"[
+        {"jsonrpc": "2.0", "method": "eth_blockNumber", "id": "1"},
+        {"jsonrpc": "2.0", "method": "eth_chainId", "params": []}
+    ]"#;
+    match single(body) {
+        RpcRequest::Batch(reqs) => {
+            assert_eq!(reqs.len(), 2);
+            assert_eq!(reqs[0].id, Id::Str("1".to_owned()));
+            assert_eq!(reqs[1].id, Id::Null);
+        }
+        RpcRequest::Single(_) => panic!("should be a batch request"),
+    }
+}
+
+#[test]
+fn rpc_call_batch_all_notifications() {
+    let body = r#"[
+        {"jsonrpc": "2.0", "method": "eth_blockNumber", "params": []},
+        {"jsonrpc": "2.0", "method": "eth_chainId"}
+    ]"#;
+    assert_eq!(process(body), None);
+}
+
+#[test]
+fn ids_are_kept_as_received() {
+    for id in ["-1", "1.50", "18446744073709551616", "1e3", r#""abc""#].iter() {
+        let body = format!(
+            r#"{{"jsonrpc": "2.0", "method": "eth_blockNumber", "id": {}}}"#,
+            id
+        );
+        let config = BatchConfig::default();
+        let resp = block_on(process_batch(&Conformance, &body, &config)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&resp).unwrap(),
+            format!(r#"{{"jsonrpc":"2.0","id":{},"result":"0x13"}}"#, id)
+        );
+    }
+}
+
+#[test]
+fn ids_are_kept_in_requests() {
+    let ids = [
+        ("1.50", Id::Number("1.50".to_owned())),
+        (
+            "18446744073709551616",
+            Id::Number("18446744073709551616".to_owned()),
+        ),
+        ("-1", Id::Number("-1".to_owned())),
+        ("7", Id::Num(7)),
+    ];
+    for (raw, id) in ids.iter() {
+        let body = format!(
+            r#"{{"jsonrpc": "2.0", "method": "eth_blockNumber", "id": {}}}"#,
+            raw
+        );
+        let req: PartialRequest = serde_json::from_str(&body).unwrap();
+        assert_eq!(req.id, *id);
+
+        match single(&body) {
+            RpcRequest::Single(req) => {
+                let req = req.complete().unwrap();
+                assert_eq!(req.id, *id);
+                assert!(serde_json::to_string(&req)
+                    .unwrap()
+                    .contains(&format!(r#""id":{}"#, raw)));
+            }
+            RpcRequest::Batch(_) => panic!("should be a single request"),
+        }
+
+        match single(&format!("[{}]", body)) {
+            RpcRequest::Batch(reqs) => assert_eq!(reqs[0].id, *id),
+            RpcRequest::Single(_) => panic!("should be a batch request"),
+        }
+    }
+}
+
+#[test]
+fn requests_from_reader_and_value() {
+    let body = r#"{"jsonrpc": "2.0", "method": "eth_blockNumber", "id": -1}"#;
+    let expected = single(body);
+    let req: RpcRequest = serde_json::from_reader(body.as_bytes()).unwrap();
+    assert_eq!(req, expected);
+    let value: Value = serde_json::from_str(body).unwrap();
+    let req: RpcRequest = serde_json::from_value(value).unwrap();
+    assert_eq!(req, expected);
+
+    let batch = format!("[{}]", body);
+    let req: RpcRequest = serde_json::from_reader(batch.as_bytes()).unwrap();
+    assert_eq!(req, single(&batch));
+    let value: Value = serde_json::from_str(&batch).unwrap();
+    let req: RpcRequest = serde_json::from_value(value).unwrap();
+    assert_eq!(req, single(&batch));
+}
+
+#[test]
+fn requests_keep_the_decode_error() {
+    let err = serde_json::from_str::<RpcRequest>(r#"[{"jsonrpc": "2.0", "id": {}}]"#)
+        .err()
+        .unwrap();
+    assert!(err.to_string().starts_with("invalid id `{}`"), "{}", err);
+
+    // Only the deserializers of `serde_json` can give the raw JSON.
+    let deserializer = serde::de::value::StrDeserializer::<serde::de::value::Error>::new("{}");
+    assert!(<RpcRequest as serde::Deserialize>::deserialize(deserializer).is_err());
+}
+
+#[test]
+fn response_objects() {
+    let success = r#"{"jsonrpc":"2.0","id":-7,"result":"0x13"}"#;
+    let resp: RpcResponse = serde_json::from_str(success).unwrap();
+    match resp {
+        RpcResponse::Single(ref output) => match **output {
+            Output::Success(ref success) => assert_eq!(success.id, Id::Number("-7".to_owned())),
+            Output::Failure(_) => panic!("should be a success"),
+        },
+        RpcResponse::Batch(_) => panic!("should be a single response"),
+    }
+    assert_eq!(serde_json::to_string(&resp).unwrap(), success);
+
+    let failure =
+        r#"{"jsonrpc":"2.0","id":"1","error":{"code":-32601,"message":"Method not found"}}"#;
+    let resp: RpcResponse = serde_json::from_str(failure).unwrap();
+    match resp {
+        RpcResponse::Single(ref output) => match **output {
+            Output::Failure(ref failure) => {
+                assert_eq!(failure.error.code, ErrorCode::MethodNotFound)
+            }
+            Output::Success(_) => panic!("should be a failure"),
+        },
+        RpcResponse::Batch(_) => panic!("should be a single response"),
+    }
+    assert_eq!(serde_json::to_string(&resp).unwrap(), failure);
+
+    let failure_with_data = r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32602,"message":"Invalid params","data":{"index":0}}}"#;
+    let batch = format!("[{},{},{}]", success, failure, failure_with_data);
+    let resp: RpcResponse = serde_json::from_str(&batch).unwrap();
+    match resp {
+        RpcResponse::Batch(ref outputs) => assert_eq!(outputs.len(), 3),
+        RpcResponse::Single(_) => panic!("should be a batch response"),
+    }
+    assert_eq!(serde_json::to_string(&resp).unwrap(), batch);
+}
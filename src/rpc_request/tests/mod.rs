@@ -14,5 +14,6 @@
 
 #![allow(clippy::cognitive_complexity)]
 
+mod conformance;
 mod params;
 mod request;
@@ -13,19 +13,29 @@
 // limitations under the License.
 
 use crate::rpc_request::{
-    eth_blockNumberParams, eth_chainIdParams, eth_getBlockByHashParams, eth_getBlockByNumberParams,
-    eth_getBlockTransactionCountByHashParams, eth_getBlockTransactionCountByNumberParams,
-    eth_getTransactionByBlockHashAndIndexParams, eth_getTransactionByBlockNumberAndIndexParams,
-    eth_getTransactionByHashParams, eth_getTransactionReceiptParams, BlockNumberParams, CallParams,
-    EstimateQuotaParams, GetAbiParams, GetBalanceParams, GetBlockByHashParams,
-    GetBlockByNumberParams, GetBlockHeaderParams, GetCensoredAddrsParams, GetCodeParams,
-    GetFilterChangesParams, GetFilterLogsParams, GetLogsParams, GetMetaDataParams,
+    debug_traceTransactionParams, eth_blockNumberParams, eth_callParams, eth_chainIdParams,
+    eth_createAccessListParams, eth_feeHistoryParams, eth_getBlockByHashParams,
+    eth_getBlockByNumberParams, eth_getBlockTransactionCountByHashParams,
+    eth_getBlockTransactionCountByNumberParams, eth_getFilterChangesParams,
+    eth_getFilterLogsParams, eth_getProofParams, eth_getTransactionByBlockHashAndIndexParams,
+    eth_getTransactionByBlockNumberAndIndexParams, eth_getTransactionByHashParams,
+    eth_getTransactionReceiptParams, eth_newBlockFilterParams, eth_newFilterParams,
+    eth_newPendingTransactionFilterParams, eth_signParams, eth_signTypedData_v4Params,
+    eth_subscribeParams, eth_uninstallFilterParams, personal_signParams, trace_filterParams,
+    BlockNumberParams, CallParams, EstimateQuotaParams, GetAbiParams, GetBalanceParams,
+    GetBlockByHashParams, GetBlockByNumberParams, GetBlockHeaderParams, GetCensoredAddrsParams,
+    GetCodeParams, GetFilterChangesParams, GetFilterLogsParams, GetLogsParams, GetMetaDataParams,
     GetStateProofParams, GetTransactionCountParams, GetTransactionParams,
     GetTransactionProofParams, GetTransactionReceiptParams, GetVersionParams, LicenseInfoParams,
     NewBlockFilterParams, NewFilterParams, OpCensoredAddressParams, PeerCountParams,
     PeersInfoParams, SendRawTransactionParams, UninstallFilterParams,
 };
-use crate::rpc_types::{BlockNumber, Boolean, CallRequest, Filter, VariadicValue};
+use crate::rpc_types::ethereum_types::{
+    AccessList, AccountOverride, BlockOverrides, Eip712Field, Eip712TypedData, EthCallRequest,
+    EthFilter, StateOverride, SubscriptionKind, Tracer, TracerConfig,
+};
+use crate::rpc_types::parity_types::trace::TraceFilter;
+use crate::rpc_types::{BlockNumber, Boolean, CallRequest, Data, Filter, VariadicValue};
 use ethereum_types::{H160, H256, U256};
 use serde_json;
 use std::convert::Into;
@@ -234,6 +244,276 @@ fn serialize_and_deserialize() {
 
     test_ser_and_de!(GetFilterLogsParams, ["0xc"], (U256::from(12).into()));
 
+    test_ser_and_de!(eth_newBlockFilterParams, [], ());
+
+    test_ser_and_de!(eth_newPendingTransactionFilterParams, [], ());
+
+    test_ser_and_de!(
+        value,
+        eth_newFilterParams,
+        [{
+            "fromBlock": "0xb",
+            "address": "0x0000000000000000000000000000000000000010",
+            "topics": null
+        }],
+        (EthFilter::new(
+            BlockNumber::new(11u64.into()),
+            BlockNumber::latest(),
+            Some(VariadicValue::single(H160::from_low_u64_be(16).into())),
+            None,
+        ))
+    );
+
+    test_ser_and_de!(
+        eth_subscribeParams,
+        ["newHeads"],
+        (SubscriptionKind::NewHeads, None)
+    );
+
+    test_ser_and_de!(
+        value,
+        eth_subscribeParams,
+        ["logs", {"address": "0x0000000000000000000000000000000000000010", "topics": null}],
+        (
+            SubscriptionKind::Logs,
+            Some(EthFilter::new(
+                BlockNumber::latest(),
+                BlockNumber::latest(),
+                Some(VariadicValue::single(H160::from_low_u64_be(16).into())),
+                None,
+            ))
+        )
+    );
+
+    test_ser_and_de!(eth_uninstallFilterParams, ["0xa"], (U256::from(10).into()));
+
+    test_ser_and_de!(eth_getFilterChangesParams, ["0xb"], (U256::from(11).into()));
+
+    test_ser_and_de!(eth_getFilterLogsParams, ["0xc"], (U256::from(12).into()));
+
+    test_ser_and_de!(
+        eth_getProofParams,
+        [
+            "0x000000000000000000000000000000000000000a",
+            ["0x000000000000000000000000000000000000000000000000000000000000000b"],
+            "latest"
+        ],
+        (
+            H160::from_low_u64_be(10).into(),
+            vec![H256::from_low_u64_be(11).into()],
+            BlockNumber::latest()
+        )
+    );
+
+    test_ser_and_de!(
+        eth_feeHistoryParams,
+        ["0x4", "latest", [25.0, 75.0]],
+        (
+            U256::from(4).into(),
+            BlockNumber::latest(),
+            vec![25.0, 75.0]
+        )
+    );
+
+    test_ser_and_de!(
+        eth_signParams,
+        ["0x000000000000000000000000000000000000000a", "0x68656c6c6f"],
+        (
+            H160::from_low_u64_be(10).into(),
+            Data::new(b"hello".to_vec())
+        )
+    );
+
+    test_ser_and_de!(
+        value,
+        eth_signTypedData_v4Params,
+        [
+            "0x000000000000000000000000000000000000000a",
+            {
+                "types": {"Person": [{"name": "name", "type": "string"}]},
+                "primaryType": "Person",
+                "domain": {"name": "Ether Mail"},
+                "message": {"name": "Cow"}
+            }
+        ],
+        (
+            H160::from_low_u64_be(10).into(),
+            Eip712TypedData {
+                types: vec![("Person".to_owned(), vec![Eip712Field::new("name", "string")])]
+                    .into_iter()
+                    .collect(),
+                primary_type: "Person".to_owned(),
+                domain: serde_json::from_value(json!({"name": "Ether Mail"})).unwrap(),
+                message: json!({"name": "Cow"}),
+            }
+        )
+    );
+
+    test_ser_and_de!(
+        personal_signParams,
+        [
+            "0x68656c6c6f",
+            "0x000000000000000000000000000000000000000a",
+            "password"
+        ],
+        (
+            Data::new(b"hello".to_vec()),
+            H160::from_low_u64_be(10).into(),
+            Some("password".to_owned())
+        )
+    );
+
+    test_ser_and_de!(
+        personal_signParams,
+        ["0x68656c6c6f", "0x000000000000000000000000000000000000000a"],
+        (
+            Data::new(b"hello".to_vec()),
+            H160::from_low_u64_be(10).into(),
+            None
+        )
+    );
+
+    test_ser_and_de!(
+        debug_traceTransactionParams,
+        [
+            "0x000000000000000000000000000000000000000000000000000000000000000b",
+            {"tracer": "callTracer"}
+        ],
+        (
+            H256::from_low_u64_be(11).into(),
+            TracerConfig {
+                tracer: Some(Tracer::CallTracer),
+                ..Default::default()
+            }
+        )
+    );
+
+    test_ser_and_de!(
+        value,
+        trace_filterParams,
+        [{
+            "fromBlock": "0x1",
+            "toAddress": ["0x000000000000000000000000000000000000000a"],
+            "count": 10
+        }],
+        (TraceFilter {
+            from_block: Some(BlockNumber::new(1u64.into())),
+            to_address: Some(vec![H160::from_low_u64_be(10).into()]),
+            count: Some(10),
+            ..Default::default()
+        })
+    );
+
+    test_ser_and_de!(
+        value,
+        eth_createAccessListParams,
+        [
+            {
+                "to": "0x000000000000000000000000000000000000000a",
+                "accessList": [{
+                    "address": "0x000000000000000000000000000000000000000a",
+                    "storageKeys": ["0x000000000000000000000000000000000000000000000000000000000000000b"]
+                }]
+            },
+            "latest"
+        ],
+        (
+            EthCallRequest {
+                to: Some(H160::from_low_u64_be(10).into()),
+                access_list: Some(vec![AccessList {
+                    address: H160::from_low_u64_be(10),
+                    storage_keys: vec![H256::from_low_u64_be(11)],
+                }]),
+                ..Default::default()
+            },
+            BlockNumber::latest()
+        )
+    );
+
+    test_ser_and_de!(
+        value,
+        eth_callParams,
+        [
+            {"to": "0x000000000000000000000000000000000000000a"},
+            "latest",
+            {
+                "0x000000000000000000000000000000000000000a": {
+                    "balance": "0x1",
+                    "state": {
+                        "0x000000000000000000000000000000000000000000000000000000000000000b":
+                            "0x000000000000000000000000000000000000000000000000000000000000000c"
+                    }
+                }
+            },
+            {"number": "0x10"}
+        ],
+        (
+            EthCallRequest {
+                to: Some(H160::from_low_u64_be(10).into()),
+                ..Default::default()
+            },
+            BlockNumber::latest(),
+            Some(StateOverride(
+                vec![(
+                    H160::from_low_u64_be(10),
+                    AccountOverride {
+                        balance: Some(U256::one()),
+                        state: Some(
+                            vec![(H256::from_low_u64_be(11), H256::from_low_u64_be(12))]
+                                .into_iter()
+                                .collect()
+                        ),
+                        ..Default::default()
+                    }
+                )]
+                .into_iter()
+                .collect()
+            )),
+            Some(BlockOverrides {
+                number: Some(U256::from(16)),
+                ..Default::default()
+            })
+        )
+    );
+
+    // The overrides absent are not serialized, unless the following ones
+    // are present.
+    test_ser_and_de!(
+        eth_callParams,
+        [{"to": "0x000000000000000000000000000000000000000a"}, "latest"],
+        (
+            EthCallRequest {
+                to: Some(H160::from_low_u64_be(10).into()),
+                ..Default::default()
+            },
+            BlockNumber::latest(),
+            None,
+            None
+        )
+    );
+
+    test_ser_and_de!(
+        eth_callParams,
+        [
+            {"to": "0x000000000000000000000000000000000000000a"},
+            "latest",
+            null,
+            {"number": "0x1"}
+        ],
+        (
+            EthCallRequest {
+                to: Some(H160::from_low_u64_be(10).into()),
+                ..Default::default()
+            },
+            BlockNumber::latest(),
+            None,
+            Some(BlockOverrides {
+                number: Some(U256::one()),
+                ..Default::default()
+            })
+        )
+    );
+
     test_ser_and_de!(
         GetTransactionProofParams,
         ["0x000000000000000000000000000000000000000000000000000000000000000b"],
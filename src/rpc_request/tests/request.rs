@@ -108,7 +108,7 @@ fn serialize_and_deserialize() {
     test_ser_and_de!(Request, full_req,  {
         "jsonrpc": "2.0",
         "id": 2,
-        "method": "blockNumber",
+        "method": "eth_blockNumber",
         "params": [],
     });
 
@@ -117,7 +117,7 @@ fn serialize_and_deserialize() {
     test_ser_and_de!(PartialRequest, part_req, {
         "jsonrpc": "2.0",
         "id": 2,
-        "method": "blockNumber",
+        "method": "eth_blockNumber",
         "params": [],
     });
 
@@ -155,6 +155,7 @@ fn serialize_and_deserialize() {
     test_ser_and_de!(PartialRequest, part_req, {
         "jsonrpc": "2.0",
         "id": null,
+        "method": "notAMethod",
     });
 }
 
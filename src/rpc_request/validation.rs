@@ -0,0 +1,258 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{self, value::RawValue};
+
+use super::rawrequest::RawRequest;
+use crate::rpc_types::{Id, Version};
+use crate::Error;
+
+/// How strictly the request object is checked against the JSON-RPC 2.0 spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Accept requests without `jsonrpc` or with `"1.0"`, unknown members are
+    /// ignored, and `id: null` is taken as a notification.
+    #[default]
+    Lenient,
+    /// Require `"jsonrpc": "2.0"`, reject unknown members and fractional ids,
+    /// and only take a request without `id` as a notification.
+    Strict,
+}
+
+// Keep a present member as `Some`, even if it is `null`.
+pub(crate) fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+// The method name, borrowed from the body if it has no escapes.
+#[derive(Deserialize)]
+pub(crate) struct Method<'a>(#[serde(borrow)] pub(crate) Cow<'a, str>);
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictRequest<'a> {
+    #[serde(default)]
+    jsonrpc: Option<Version>,
+    #[serde(default, borrow, deserialize_with = "deserialize_present")]
+    id: Option<&'a RawValue>,
+    #[serde(borrow)]
+    method: Method<'a>,
+    #[serde(default, borrow, deserialize_with = "deserialize_present")]
+    params: Option<&'a RawValue>,
+}
+
+// Whether the JSON number has no fractional part, `1e3` and `1.50e1` are
+// integral. Told from the digits, so a long number does not lose precision.
+fn is_integral(number: &str) -> bool {
+    let number = number.trim_start_matches('-');
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(index) => {
+            let exponent = &number[index + 1..];
+            let saturated = if exponent.starts_with('-') {
+                i64::MIN
+            } else {
+                i64::MAX
+            };
+            (&number[..index], exponent.parse().unwrap_or(saturated))
+        }
+        None => (number, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction);
+    let significant = digits.trim_end_matches('0').len() as i64;
+    significant == 0 || significant <= (integer.len() as i64).saturating_add(exponent)
+}
+
+fn parse_strict_id(id: &RawValue) -> Result<Id, Error> {
+    let id = id.get();
+    match id.bytes().next() {
+        Some(b'"') | Some(b'n') => {}
+        Some(b'-') | Some(b'0'..=b'9') => {
+            if !is_integral(id) {
+                return Err(Error::invalid_request_with_message(
+                    "id should not contain fractional parts",
+                ));
+            }
+        }
+        _ => {
+            return Err(Error::invalid_request_with_message(
+                "id should be a string, a number or null",
+            ))
+        }
+    }
+    serde_json::from_str(id).map_err(|err| Error::invalid_request_with_message(err.to_string()))
+}
+
+impl ValidationMode {
+    /// Parse a request object, and check its members according to the mode.
+    pub fn parse_request<'a>(self, entry: &'a str) -> Result<RawRequest<'a>, Error> {
+        match self {
+            ValidationMode::Lenient => {
+                let mut req: RawRequest = serde_json::from_str(entry).map_err(|err| {
+                    if err.is_data() {
+                        Error::invalid_request()
+                    } else {
+                        Error::parse_error_with_message(err.to_string())
+                    }
+                })?;
+                if req.id == Some(Id::Null) {
+                    req.id = None;
+                }
+                Ok(req)
+            }
+            ValidationMode::Strict => {
+                let req: StrictRequest = serde_json::from_str(entry).map_err(|err| {
+                    if err.is_data() {
+                        Error::invalid_request_with_message(err.to_string())
+                    } else {
+                        Error::parse_error_with_message(err.to_string())
+                    }
+                })?;
+                if req.jsonrpc != Some(Version::V2) {
+                    return Err(Error::invalid_version());
+                }
+                if let Some(params) = req.params {
+                    if !params.get().starts_with(['[', '{']) {
+                        return Err(Error::invalid_request_with_message(
+                            "params should be an array or an object",
+                        ));
+                    }
+                }
+                let id = req.id.map(parse_strict_id).transpose()?;
+                Ok(RawRequest {
+                    jsonrpc: req.jsonrpc,
+                    id,
+                    method: Some(req.method.0),
+                    params: req.params,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorCode;
+
+    fn strict_error(entry: &str) -> Error {
+        ValidationMode::Strict.parse_request(entry).unwrap_err()
+    }
+
+    #[test]
+    fn test_strict_valid_requests() {
+        let req = ValidationMode::Strict
+            .parse_request(r#"{"jsonrpc": "2.0", "id": "a", "method": "eth_call", "params": []}"#)
+            .unwrap();
+        assert_eq!(req.id, Some(Id::Str("a".to_owned())));
+        assert!(!req.is_notification());
+
+        let req = ValidationMode::Strict
+            .parse_request(r#"{"jsonrpc": "2.0", "id": null, "method": "eth_blockNumber"}"#)
+            .unwrap();
+        assert_eq!(req.id, Some(Id::Null));
+        assert!(!req.is_notification());
+
+        let req = ValidationMode::Strict
+            .parse_request(r#"{"jsonrpc": "2.0", "method": "eth_blockNumber"}"#)
+            .unwrap();
+        assert!(req.is_notification());
+
+        // Integral numbers in the other notations are kept as received.
+        for id in ["1e3", "-1.0", "1.50e1", "12000E-3", "0.0e-7"] {
+            let entry = format!(
+                r#"{{"jsonrpc": "2.0", "id": {}, "method": "eth_blockNumber"}}"#,
+                id
+            );
+            let req = ValidationMode::Strict.parse_request(&entry).unwrap();
+            assert_eq!(req.id, Some(Id::Number(id.to_owned())));
+        }
+    }
+
+    #[test]
+    fn test_strict_invalid_requests() {
+        assert_eq!(
+            strict_error(r#"{"id": 1, "method": "eth_blockNumber"}"#),
+            Error::invalid_version()
+        );
+        assert_eq!(
+            strict_error(r#"{"jsonrpc": "1.0", "id": 1, "method": "eth_blockNumber"}"#),
+            Error::invalid_version()
+        );
+        assert_eq!(
+            strict_error(r#"{"jsonrpc": "2.0", "id": 1.5, "method": "eth_blockNumber"}"#),
+            Error::invalid_request_with_message("id should not contain fractional parts")
+        );
+        assert_eq!(
+            strict_error(r#"{"jsonrpc": "2.0", "id": 1e-3, "method": "eth_blockNumber"}"#),
+            Error::invalid_request_with_message("id should not contain fractional parts")
+        );
+        assert_eq!(
+            strict_error(r#"{"jsonrpc": "2.0", "id": 1.25e1, "method": "eth_blockNumber"}"#),
+            Error::invalid_request_with_message("id should not contain fractional parts")
+        );
+        assert_eq!(
+            strict_error(r#"{"jsonrpc": "2.0", "id": true, "method": "eth_blockNumber"}"#),
+            Error::invalid_request_with_message("id should be a string, a number or null")
+        );
+        assert_eq!(
+            strict_error(r#"{"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": null}"#),
+            Error::invalid_request_with_message("params should be an array or an object")
+        );
+        for entry in [
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "foo": 1}"#,
+            r#"{"jsonrpc": "2.0", "id": 1}"#,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": 1}"#,
+            r#"1"#,
+        ]
+        .iter()
+        {
+            assert_eq!(
+                strict_error(entry).code,
+                ErrorCode::InvalidRequest,
+                "{}",
+                entry
+            );
+        }
+        assert_eq!(strict_error(r#"{"jsonrpc": "#).code, ErrorCode::ParseError);
+    }
+
+    #[test]
+    fn test_lenient_requests() {
+        for entry in [
+            r#"{"id": 1, "method": "eth_blockNumber"}"#,
+            r#"{"jsonrpc": "1.0", "id": 1, "method": "eth_blockNumber", "foo": 1}"#,
+        ]
+        .iter()
+        {
+            let req = ValidationMode::Lenient.parse_request(entry).unwrap();
+            assert_eq!(req.id, Some(Id::Num(1)));
+        }
+        let req = ValidationMode::Lenient
+            .parse_request(r#"{"jsonrpc": "2.0", "id": null, "method": "eth_blockNumber"}"#)
+            .unwrap();
+        assert!(req.is_notification());
+        assert_eq!(
+            ValidationMode::Lenient.parse_request("1").unwrap_err(),
+            Error::invalid_request()
+        );
+    }
+}
@@ -12,14 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
 use std::vec::Vec;
 
-use serde::de::Error as SError;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error as SError, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use serde_json::{from_value, Value};
 
 use crate::error::Error;
-use crate::rpc_request::{RequestInfo, ResponseResult};
+use crate::rpc_request::{deserialize_present, RequestInfo, ResponseResult};
 use crate::rpc_types::{Id, Version};
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -75,16 +76,39 @@ impl Output {
     }
 }
 
+// The members of a response object, decoded in one pass. It is a failure if
+// it has `error`, otherwise a success with `result`, which may be `null`.
+#[derive(Deserialize)]
+struct RawOutput {
+    jsonrpc: Option<Version>,
+    id: Id,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    result: Option<ResponseResult>,
+    #[serde(default)]
+    error: Option<Error>,
+}
+
 impl<'a> Deserialize<'a> for Output {
     fn deserialize<D>(deserializer: D) -> Result<Output, D::Error>
     where
         D: Deserializer<'a>,
     {
-        let v: Value = Deserialize::deserialize(deserializer)?;
-        from_value(v.clone())
-            .map(Output::Failure)
-            .or_else(|_| from_value(v).map(Output::Success))
-            .map_err(|_| D::Error::custom("")) // types must match
+        let RawOutput {
+            jsonrpc,
+            id,
+            result,
+            error,
+        } = RawOutput::deserialize(deserializer)?;
+        match (error, result) {
+            (Some(error), _) => Ok(Output::Failure(RpcFailure { jsonrpc, id, error })),
+            (None, Some(result)) => Ok(RpcSuccess {
+                jsonrpc,
+                id,
+                result,
+            }
+            .output()),
+            (None, None) => Err(D::Error::missing_field("result")),
+        }
     }
 }
 
@@ -125,15 +149,42 @@ pub enum RpcResponse {
 }
 
 impl<'a> Deserialize<'a> for RpcResponse {
+    /// A batch or a single response is decided by the JSON type, an array or
+    /// an object.
     fn deserialize<D>(deserializer: D) -> Result<RpcResponse, D::Error>
     where
         D: Deserializer<'a>,
     {
-        let v: Value = Deserialize::deserialize(deserializer)?;
-        from_value(v.clone())
-            .map(RpcResponse::Batch)
-            .or_else(|_| from_value(v).map(RpcResponse::Single))
-            .map_err(|_| D::Error::custom("")) // types must match
+        struct RpcResponseVisitor;
+
+        impl<'a> Visitor<'a> for RpcResponseVisitor {
+            type Value = RpcResponse;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a response object or an array of them")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'a>,
+            {
+                let mut outputs = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(output) = seq.next_element()? {
+                    outputs.push(output);
+                }
+                Ok(RpcResponse::Batch(outputs))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'a>,
+            {
+                Output::deserialize(MapAccessDeserializer::new(map))
+                    .map(|output| RpcResponse::Single(Box::new(output)))
+            }
+        }
+
+        deserializer.deserialize_any(RpcResponseVisitor)
     }
 }
 
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{hex_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::basic::utils::LowerHex;
 use rustc_serialize::hex::FromHex;
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 /// Arbitrary length bytes (wrapper structure around vector of bytes).
 #[derive(Debug, PartialEq, Eq, Default, Hash, Clone)]
@@ -109,6 +111,12 @@ impl Into<Vec<u8>> for Data {
     }
 }
 
+impl OpenRpcSchema for Data {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Data", |_| hex_schema("Data", "^0x([0-9a-fA-F]{2})*$"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Data;
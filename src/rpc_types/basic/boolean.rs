@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{OpenRpcComponents, OpenRpcSchema};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
 
 /// A unsigned integer (wrapper structure around bool).
 #[derive(Debug, PartialEq, Eq, Default, Hash, Clone)]
@@ -72,6 +74,15 @@ impl Into<bool> for Boolean {
     }
 }
 
+impl OpenRpcSchema for Boolean {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference(
+            "Boolean",
+            |_| json!({ "title": "Boolean", "type": "boolean" }),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Boolean;
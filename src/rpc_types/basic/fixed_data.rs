@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{hex_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::basic::utils::LowerHex;
 use ethereum_types::{H160, H256};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 use std::str::FromStr;
 
 /// Fixed length bytes (wrapper structure around H256).
@@ -127,6 +129,18 @@ macro_rules! impl_for_fixed_type {
 impl_for_fixed_type!(Data32, H256, 32usize, Data32Visitor);
 impl_for_fixed_type!(Data20, H160, 20usize, Data20Visitor);
 
+impl OpenRpcSchema for Data32 {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Data32", |_| hex_schema("Data32", "^0x[0-9a-fA-F]{64}$"))
+    }
+}
+
+impl OpenRpcSchema for Data20 {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Data20", |_| hex_schema("Data20", "^0x[0-9a-fA-F]{40}$"))
+    }
+}
+
 macro_rules! test_for_fixed_type {
     ($test_name:ident, $outer:ident, $inner:ident, $outer_size:expr) => {
         #[cfg(test)]
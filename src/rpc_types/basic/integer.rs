@@ -15,7 +15,9 @@
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::rpc_request::{OpenRpcComponents, OpenRpcSchema};
 use crate::Error;
+use serde_json::{json, Value};
 
 /// A unsigned integer (wrapper structure around u64).
 #[derive(Debug, PartialEq, Eq, Default, Hash, Clone)]
@@ -41,7 +43,7 @@ impl<'de> Deserialize<'de> for Integer {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_str(IntegerVisitor)
+        deserializer.deserialize_any(IntegerVisitor)
     }
 }
 
@@ -54,6 +56,13 @@ impl<'de> Visitor<'de> for IntegerVisitor {
         formatter.write_str("Integer")
     }
 
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Integer::new(value))
+    }
+
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -150,6 +159,22 @@ impl_convert_with_uint!(u32);
 impl_convert_with_uint!(u16);
 impl_convert_with_uint!(u8);
 
+impl OpenRpcSchema for Integer {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        // Serialized as a number, deserialized from a number or a hex or
+        // decimal string.
+        components.reference("Integer", |_| {
+            json!({
+                "title": "Integer",
+                "oneOf": [
+                    { "type": "integer", "minimum": 0 },
+                    { "type": "string", "pattern": "^(0[xX][0-9a-fA-F]+|[0-9]+)$" },
+                ],
+            })
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Integer;
@@ -167,8 +192,11 @@ mod tests {
     fn deserialize() {
         let testdata = vec![
             (r#""""#, None),
-            (r#""0""#, None),
-            (r#""10""#, None),
+            (r#""0""#, Some(0u64)),
+            (r#""10""#, Some(10u64)),
+            (r#""0xa""#, Some(10u64)),
+            (r#""0x""#, None),
+            (r#"-1"#, None),
             (r#""#, None),
             (r#"a"#, None),
             (r#"0"#, Some(0u64)),
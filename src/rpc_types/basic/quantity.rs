@@ -15,10 +15,12 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
+use crate::rpc_request::{hex_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::basic::utils::LowerHex;
 use ethereum_types::U256;
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 /// A big unsigned integer (wrapper structure around U256).
 #[derive(Debug, PartialEq, Eq, Default, Hash, Clone)]
@@ -161,6 +163,14 @@ impl_from_and_into_for_small_uint!(u32);
 impl_from_and_into_for_small_uint!(u16);
 impl_from_and_into_for_small_uint!(u8);
 
+impl OpenRpcSchema for Quantity {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Quantity", |_| {
+            hex_schema("Quantity", "^0x([1-9a-fA-F][0-9a-fA-F]*|0)$")
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Quantity;
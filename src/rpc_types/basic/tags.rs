@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{OpenRpcComponents, OpenRpcSchema};
+use serde_json::{json, Value};
 use std::fmt::Display;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -47,6 +49,30 @@ impl Display for BlockTag {
     }
 }
 
+impl OpenRpcSchema for BlockTag {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockTag", |_| {
+            json!({
+                "title": "BlockTag",
+                "type": "string",
+                "enum": ["latest", "earliest", "pending", "safe", "finalized"],
+            })
+        })
+    }
+}
+
+impl OpenRpcSchema for EconomicalModel {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EconomicalModel", |_| {
+            json!({
+                "title": "EconomicalModel",
+                "type": "integer",
+                "enum": [0, 1],
+            })
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests_tags {
     use super::{BlockTag, EconomicalModel};
@@ -12,9 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use serde::de::{self, DeserializeOwned};
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
-use serde_json::{self, Value};
+use serde_json::{json, Value};
+
+use crate::rpc_request::{OpenRpcComponents, OpenRpcSchema};
 
 /// Variadic value.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize)]
@@ -50,20 +56,87 @@ impl<'de, T> Deserialize<'de> for VariadicValue<T>
 where
     T: DeserializeOwned + Serialize,
 {
+    /// `null` is `Null` and an array is `Multiple`, any other value is decoded
+    /// as a `Single` in the same pass.
     fn deserialize<D>(deserializer: D) -> Result<VariadicValue<T>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let v: Value = Deserialize::deserialize(deserializer)?;
+        struct VariadicVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for VariadicVisitor<T>
+        where
+            T: DeserializeOwned + Serialize,
+        {
+            type Value = VariadicValue<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("null, a value or an array of values")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(VariadicValue::Null)
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(VariadicValue::Null)
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                T::deserialize(value.into_deserializer()).map(VariadicValue::Single)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                T::deserialize(value.into_deserializer()).map(VariadicValue::Single)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                T::deserialize(value.into_deserializer()).map(VariadicValue::Single)
+            }
 
-        if v.is_null() {
-            return Ok(VariadicValue::Null);
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+                T::deserialize(value.into_deserializer()).map(VariadicValue::Single)
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                T::deserialize(value.into_deserializer()).map(VariadicValue::Single)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                T::deserialize(MapAccessDeserializer::new(map)).map(VariadicValue::Single)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(VariadicValue::Multiple(values))
+            }
         }
 
-        serde_json::from_value(v.clone())
-            .map(VariadicValue::Single)
-            .or_else(|_| serde_json::from_value(v).map(VariadicValue::Multiple))
-            .map_err(|_| de::Error::custom("invalid type"))
+        deserializer.deserialize_any(VariadicVisitor(PhantomData))
+    }
+}
+
+impl<T> OpenRpcSchema for VariadicValue<T>
+where
+    T: DeserializeOwned + Serialize + OpenRpcSchema,
+{
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        json!({
+            "oneOf": [
+                { "type": "null" },
+                T::schema(components),
+                { "type": "array", "items": T::schema(components) },
+            ],
+        })
     }
 }
 
@@ -91,5 +164,7 @@ mod tests {
         test_ser_and_de!(VariadicU64, 123, VariadicU64::single(123));
         test_ser_and_de!(VariadicU64, [123], VariadicU64::multiple(vec![123]));
         test_ser_and_de!(VariadicU64, [1, 2, 3], VariadicU64::multiple(vec![1, 2, 3]));
+        assert!(serde_json::from_str::<VariadicU64>("\"0x1\"").is_err());
+        assert!(serde_json::from_str::<VariadicU64>("[1, \"0x1\"]").is_err());
     }
 }
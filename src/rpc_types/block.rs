@@ -14,7 +14,10 @@
 
 use ethereum_types::{Address, H256, U256};
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::{BlockTransaction, Proof};
+use crate::rpc_types::{Data20, Data32, Quantity};
+use serde_json::Value;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct BlockBody {
@@ -46,3 +49,64 @@ pub struct Block {
     pub header: BlockHeader,
     pub body: BlockBody,
 }
+
+impl OpenRpcSchema for BlockBody {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockBody", |components| {
+            object_schema(
+                "BlockBody",
+                vec![("transactions", components.schema::<Vec<BlockTransaction>>())],
+                &["transactions"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for BlockHeader {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockHeader", |components| {
+            object_schema(
+                "BlockHeader",
+                vec![
+                    ("timestamp", components.schema::<u64>()),
+                    ("prevHash", components.schema::<Data32>()),
+                    ("number", components.schema::<Quantity>()),
+                    ("stateRoot", components.schema::<Data32>()),
+                    ("transactionsRoot", components.schema::<Data32>()),
+                    ("receiptsRoot", components.schema::<Data32>()),
+                    ("quotaUsed", components.schema::<Quantity>()),
+                    ("proof", components.schema::<Option<Proof>>()),
+                    ("proposer", components.schema::<Data20>()),
+                ],
+                &[
+                    "timestamp",
+                    "prevHash",
+                    "number",
+                    "stateRoot",
+                    "transactionsRoot",
+                    "receiptsRoot",
+                    "quotaUsed",
+                    "proof",
+                    "proposer",
+                ],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for Block {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Block", |components| {
+            object_schema(
+                "Block",
+                vec![
+                    ("version", components.schema::<u32>()),
+                    ("hash", components.schema::<Data32>()),
+                    ("header", components.schema::<BlockHeader>()),
+                    ("body", components.schema::<BlockBody>()),
+                ],
+                &["version", "hash", "header", "body"],
+            )
+        })
+    }
+}
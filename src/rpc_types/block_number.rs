@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::{BlockTag, Data32, Quantity};
 use cita_cloud_proto::evm::{self, block_number::Lable};
 use cita_tool::H256;
+use serde_json::{json, Value};
 use std::fmt::Display;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
@@ -92,6 +94,21 @@ impl Into<evm::BlockNumber> for BlockNumber {
     }
 }
 
+impl OpenRpcSchema for BlockNumber {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockNumber", |components| {
+            json!({
+                "title": "BlockNumber",
+                "oneOf": [
+                    components.schema::<BlockTag>(),
+                    components.schema::<Quantity>(),
+                    components.schema::<Data32>(),
+                ],
+            })
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::BlockNumber;
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::{Data, Data20};
+use serde_json::Value;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, Default)]
 pub struct CallRequest {
@@ -29,6 +31,22 @@ impl CallRequest {
     }
 }
 
+impl OpenRpcSchema for CallRequest {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("CallRequest", |components| {
+            object_schema(
+                "CallRequest",
+                vec![
+                    ("from", components.schema::<Data20>()),
+                    ("to", components.schema::<Data20>()),
+                    ("data", components.schema::<Data>()),
+                ],
+                &["to"],
+            )
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::CallRequest;
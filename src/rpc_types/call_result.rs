@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::Data;
+use crate::rpc_types::Quantity;
 use ethereum_types::U256;
+use serde_json::Value;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, Default)]
 pub struct CallResult {
@@ -28,3 +31,20 @@ pub struct CallResult {
     #[serde(rename = "errorMessage")]
     pub error_message: String,
 }
+
+impl OpenRpcSchema for CallResult {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("CallResult", |components| {
+            object_schema(
+                "CallResult",
+                vec![
+                    ("data", components.schema::<Data>()),
+                    ("quotaUsed", components.schema::<Option<Quantity>>()),
+                    ("code", components.schema::<u64>()),
+                    ("errorMessage", components.schema::<String>()),
+                ],
+                &["data", "quotaUsed", "code", "errorMessage"],
+            )
+        })
+    }
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data20;
 use ethereum_types::H160;
+use serde_json::Value;
 
 /// censor addresses
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
@@ -20,3 +23,15 @@ pub struct CensorAddrs {
     /// addresses
     pub addrs: Vec<H160>,
 }
+
+impl OpenRpcSchema for CensorAddrs {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("CensorAddrs", |components| {
+            object_schema(
+                "CensorAddrs",
+                vec![("addrs", components.schema::<Vec<Data20>>())],
+                &["addrs"],
+            )
+        })
+    }
+}
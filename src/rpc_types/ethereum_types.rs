@@ -1,11 +1,41 @@
 mod block;
 mod call_request;
+mod debug_trace;
+mod fee_history;
 mod filter;
+mod proof;
 mod receipt;
+mod state_override;
+mod subscription;
 mod transaction;
+mod txpool;
+mod typed_data;
 
 pub use self::block::{EthBlock, EthBlockHeader};
 pub use self::call_request::{EthCallRequest, EthTransactionRequest};
-pub use self::filter::EthFilter;
+pub use self::debug_trace::{
+    BlockTraceResult, CallFrame, CallLogFrame, CallTracerConfig, DebugTrace, StructLog,
+    StructLogTrace, Tracer, TracerConfig,
+};
+pub use self::fee_history::{
+    fee_history_range, next_base_fee, validate_reward_percentiles, FeeHistory,
+    MAX_FEE_HISTORY_BLOCKS,
+};
+pub use self::filter::{EthFilter, EthFilterChanges};
+pub use self::proof::{EthAccountProof, EthStorageProof, StateProof};
 pub use self::receipt::{EthLog, EthReceipt};
-pub use self::transaction::{EthBlockTransaction, EthRpcTransaction};
+pub use self::state_override::{
+    AccountOverride, BlockOverrides, ExecutorAccountOverride, ExecutorBlockOverrides,
+    ExecutorStorage, StateOverride,
+};
+pub use self::subscription::{
+    SubscriptionId, SubscriptionKind, SubscriptionNotification, SubscriptionParams,
+    SubscriptionResult, SyncStatus, SyncingResult, SUBSCRIPTION_METHOD,
+};
+pub use self::transaction::{
+    raw_transaction_hash, AccessList, AccessListWithGasUsed, EthBlockTransaction, EthRpcTransaction,
+};
+pub use self::txpool::{TxpoolContent, TxpoolGroup, TxpoolInspect, TxpoolStatus};
+pub use self::typed_data::{
+    Eip712Domain, Eip712Field, Eip712TypedData, Eip712Types, EIP712_DOMAIN,
+};
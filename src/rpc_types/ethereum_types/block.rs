@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::rpc_types::{ethereum_types::EthBlockTransaction, Block, BlockHeader, Data};
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::ethereum_types::{raw_transaction_hash, EthBlockTransaction};
+use crate::rpc_types::{Block, BlockHeader, Data};
+use crate::rpc_types::{Data20, Data32, Quantity};
 use ethereum_types::{Address, Bloom, H256, H64, U256};
+use serde_json::Value;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -134,7 +138,13 @@ impl From<cita_cloud_proto::blockchain::Block> for EthBlock {
                 .unwrap()
                 .body
                 .into_iter()
-                .map(EthBlockTransaction::from)
+                // The transactions can't be read are kept as the hashes, so
+                // none of them is missing from the block.
+                .map(|raw| {
+                    let hash = raw_transaction_hash(&raw);
+                    EthBlockTransaction::try_from_raw(raw)
+                        .unwrap_or(EthBlockTransaction::Hash(hash))
+                })
                 .collect(),
             uncles: vec![],
         }
@@ -157,3 +167,88 @@ impl From<cita_cloud_proto::blockchain::CompactBlock> for EthBlock {
         }
     }
 }
+
+impl OpenRpcSchema for EthBlock {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthBlock", |components| {
+            object_schema(
+                "EthBlock",
+                vec![
+                    ("parentHash", components.schema::<Data32>()),
+                    ("sha3Uncles", components.schema::<Data32>()),
+                    ("miner", components.schema::<Data20>()),
+                    ("stateRoot", components.schema::<Data32>()),
+                    ("transactionsRoot", components.schema::<Data32>()),
+                    ("receiptsRoot", components.schema::<Data32>()),
+                    ("logsBloom", components.schema::<Data>()),
+                    ("difficulty", components.schema::<Quantity>()),
+                    ("number", components.schema::<Quantity>()),
+                    ("gasLimit", components.schema::<Quantity>()),
+                    ("gasUsed", components.schema::<Quantity>()),
+                    ("timestamp", components.schema::<Quantity>()),
+                    ("extraData", components.schema::<Data>()),
+                    ("mixHash", components.schema::<Data32>()),
+                    ("nonce", components.schema::<Data>()),
+                    ("baseFeePerGas", components.schema::<Quantity>()),
+                    ("hash", components.schema::<Data32>()),
+                    ("totalDifficulty", components.schema::<Quantity>()),
+                    ("size", components.schema::<Quantity>()),
+                    (
+                        "transactions",
+                        components.schema::<Vec<EthBlockTransaction>>(),
+                    ),
+                    ("uncles", components.schema::<Vec<Data32>>()),
+                ],
+                &["parentHash", "number", "hash", "timestamp", "uncles"],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cita_cloud_proto::blockchain::{
+        raw_transaction, BlockHeader as CloudBlockHeader, RawTransaction, RawTransactions,
+        Transaction, UnverifiedTransaction, Witness,
+    };
+
+    fn transaction(n: u64, signature: Vec<u8>) -> RawTransaction {
+        RawTransaction {
+            tx: Some(raw_transaction::Tx::NormalTx(UnverifiedTransaction {
+                transaction: Some(Transaction::default()),
+                transaction_hash: H256::from_low_u64_be(n).as_bytes().to_vec(),
+                witness: Some(Witness {
+                    signature,
+                    sender: Address::from_low_u64_be(n).as_bytes().to_vec(),
+                }),
+            })),
+        }
+    }
+
+    #[test]
+    fn test_block_keeps_unreadable_transactions() {
+        let block = cita_cloud_proto::blockchain::Block {
+            header: Some(CloudBlockHeader {
+                prevhash: vec![0; 32],
+                transactions_root: vec![0; 32],
+                proposer: vec![0; 20],
+                ..Default::default()
+            }),
+            body: Some(RawTransactions {
+                body: vec![transaction(1, vec![0; 65]), transaction(2, vec![])],
+            }),
+            ..Default::default()
+        };
+        let block = EthBlock::from(block);
+        assert_eq!(block.transactions.len(), 2);
+        match block.transactions[0] {
+            EthBlockTransaction::Full(ref tx) => assert_eq!(tx.hash, H256::from_low_u64_be(1)),
+            EthBlockTransaction::Hash(_) => panic!("should be a full transaction"),
+        }
+        assert_eq!(
+            block.transactions[1],
+            EthBlockTransaction::Hash(H256::from_low_u64_be(2))
+        );
+    }
+}
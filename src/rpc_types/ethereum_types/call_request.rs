@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::ethereum_types::transaction::AccessList;
 use crate::rpc_types::{Data, Data20, Integer, Quantity};
+use serde_json::Value;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -39,7 +41,7 @@ pub struct EthCallRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_priority_fee_per_gas: Option<Quantity>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub access_list: Option<AccessList>,
+    pub access_list: Option<Vec<AccessList>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, Default)]
@@ -66,9 +68,13 @@ pub struct EthTransactionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_priority_fee_per_gas: Option<Quantity>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub access_list: Option<AccessList>,
+    pub access_list: Option<Vec<AccessList>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub condition: Option<TransactionCondition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Quantity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<Quantity>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -81,3 +87,39 @@ pub enum TransactionCondition {
     #[serde(rename = "time")]
     Timestamp(u64),
 }
+
+// The fields shared by `EthCallRequest` and `EthTransactionRequest`.
+fn call_properties(components: &mut OpenRpcComponents) -> Vec<(&'static str, Value)> {
+    vec![
+        ("from", components.schema::<Data20>()),
+        ("to", components.schema::<Data20>()),
+        ("input", components.schema::<Data>()),
+        ("data", components.schema::<Data>()),
+        ("value", components.schema::<Quantity>()),
+        ("gas", components.schema::<Quantity>()),
+        ("gasPrice", components.schema::<Quantity>()),
+        ("type", components.schema::<Integer>()),
+        ("maxFeePerGas", components.schema::<Quantity>()),
+        ("maxPriorityFeePerGas", components.schema::<Quantity>()),
+        ("accessList", components.schema::<Vec<AccessList>>()),
+    ]
+}
+
+impl OpenRpcSchema for EthCallRequest {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthCallRequest", |components| {
+            object_schema("EthCallRequest", call_properties(components), &[])
+        })
+    }
+}
+
+impl OpenRpcSchema for EthTransactionRequest {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthTransactionRequest", |components| {
+            let mut properties = call_properties(components);
+            properties.push(("nonce", components.schema::<Quantity>()));
+            properties.push(("chainId", components.schema::<Quantity>()));
+            object_schema("EthTransactionRequest", properties, &[])
+        })
+    }
+}
@@ -0,0 +1,478 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types of `debug_traceTransaction`, `debug_traceCall` and
+//! `debug_traceBlockByNumber`, in the format of geth.
+
+use std::collections::BTreeMap;
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data;
+use crate::rpc_types::{Data20, Data32, Quantity};
+use crate::Error;
+use ethereum_types::{Address, H256, U256};
+use serde_json::{json, Value};
+
+/// The tracer of the options, the struct logger is used without any.
+///
+/// Only the output of `callTracer` is typed, the other built-in tracers and
+/// the JavaScript tracers are kept as they are, to be forwarded to a node.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Tracer {
+    CallTracer,
+    PrestateTracer,
+    FourByteTracer,
+    /// A tracer unknown here, or the code of a JavaScript tracer.
+    Other(String),
+}
+
+impl From<String> for Tracer {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "callTracer" => Tracer::CallTracer,
+            "prestateTracer" => Tracer::PrestateTracer,
+            "4byteTracer" => Tracer::FourByteTracer,
+            _ => Tracer::Other(name),
+        }
+    }
+}
+
+impl From<Tracer> for String {
+    fn from(tracer: Tracer) -> Self {
+        match tracer {
+            Tracer::CallTracer => "callTracer".to_owned(),
+            Tracer::PrestateTracer => "prestateTracer".to_owned(),
+            Tracer::FourByteTracer => "4byteTracer".to_owned(),
+            Tracer::Other(name) => name,
+        }
+    }
+}
+
+/// Config of `callTracer`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTracerConfig {
+    /// Trace the top call only, without the sub calls.
+    #[serde(default)]
+    pub only_top_call: bool,
+    /// Collect the logs emitted by the calls.
+    #[serde(default)]
+    pub with_log: bool,
+}
+
+/// Options of the tracing methods.
+///
+/// The `disable*` and `enable*` fields are for the struct logger, and the
+/// `tracerConfig` is for the tracer, which is opaque as it depends on the
+/// tracer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disable_storage: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disable_stack: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub enable_memory: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub enable_return_data: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracer: Option<Tracer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracer_config: Option<Value>,
+    /// Duration as `5s`, `300ms`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
+impl TracerConfig {
+    /// The config of `callTracer`, the default one if it is absent.
+    pub fn call_tracer_config(&self) -> Result<CallTracerConfig, Error> {
+        match self.tracer_config {
+            Some(ref config) => serde_json::from_value(config.clone())
+                .map_err(|err| Error::invalid_params(format!("invalid tracerConfig: {}", err))),
+            None => Ok(CallTracerConfig::default()),
+        }
+    }
+}
+
+/// One step of the execution.
+///
+/// The memory words and the storage are hex strings without `0x`, as geth.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u64,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_data: Option<Data>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refund: Option<u64>,
+}
+
+/// Output of the struct logger.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogTrace {
+    pub failed: bool,
+    pub gas: u64,
+    /// Hex string without `0x`.
+    pub return_value: String,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// A log emitted in a call frame.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct CallLogFrame {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Data,
+}
+
+/// Output of `callTracer`, a call with its sub calls.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// `CALL`, `STATICCALL`, `DELEGATECALL`, `CREATE`, `CREATE2` or
+    /// `SELFDESTRUCT`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub from: Address,
+    pub gas: U256,
+    pub gas_used: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    pub input: Data,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Data>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<CallLogFrame>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+}
+
+/// Result of a tracing method, decided by the tracer of the options.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    StructLogs(StructLogTrace),
+    CallTracer(Box<CallFrame>),
+    /// Output of the other tracers.
+    Other(Value),
+}
+
+/// Trace of a transaction in a block, with the result or the error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTraceResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<H256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<DebugTrace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OpenRpcSchema for TracerConfig {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TracerConfig", |components| {
+            object_schema(
+                "TracerConfig",
+                vec![
+                    ("disableStorage", components.schema::<bool>()),
+                    ("disableStack", components.schema::<bool>()),
+                    ("enableMemory", components.schema::<bool>()),
+                    ("enableReturnData", components.schema::<bool>()),
+                    ("tracer", components.schema::<String>()),
+                    ("tracerConfig", json!({ "type": "object" })),
+                    ("timeout", components.schema::<String>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for StructLog {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("StructLog", |components| {
+            object_schema(
+                "StructLog",
+                vec![
+                    ("pc", components.schema::<u64>()),
+                    ("op", components.schema::<String>()),
+                    ("gas", components.schema::<u64>()),
+                    ("gasCost", components.schema::<u64>()),
+                    ("depth", components.schema::<u64>()),
+                    ("error", components.schema::<String>()),
+                    ("stack", components.schema::<Vec<Quantity>>()),
+                    ("memory", components.schema::<Vec<String>>()),
+                    ("storage", components.schema::<BTreeMap<String, String>>()),
+                    ("returnData", components.schema::<Data>()),
+                    ("refund", components.schema::<u64>()),
+                ],
+                &["pc", "op", "gas", "gasCost", "depth"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for StructLogTrace {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("StructLogTrace", |components| {
+            object_schema(
+                "StructLogTrace",
+                vec![
+                    ("failed", components.schema::<bool>()),
+                    ("gas", components.schema::<u64>()),
+                    ("returnValue", components.schema::<String>()),
+                    ("structLogs", components.schema::<Vec<StructLog>>()),
+                ],
+                &["failed", "gas", "returnValue", "structLogs"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for CallLogFrame {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("CallLogFrame", |components| {
+            object_schema(
+                "CallLogFrame",
+                vec![
+                    ("address", components.schema::<Data20>()),
+                    ("topics", components.schema::<Vec<Data32>>()),
+                    ("data", components.schema::<Data>()),
+                ],
+                &["address", "topics", "data"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for CallFrame {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("CallFrame", |components| {
+            let type_ = json!({
+                "type": "string",
+                "enum": [
+                    "CALL",
+                    "STATICCALL",
+                    "DELEGATECALL",
+                    "CALLCODE",
+                    "CREATE",
+                    "CREATE2",
+                    "SELFDESTRUCT",
+                ],
+            });
+            object_schema(
+                "CallFrame",
+                vec![
+                    ("type", type_),
+                    ("from", components.schema::<Data20>()),
+                    ("gas", components.schema::<Quantity>()),
+                    ("gasUsed", components.schema::<Quantity>()),
+                    ("to", components.schema::<Data20>()),
+                    ("input", components.schema::<Data>()),
+                    ("output", components.schema::<Data>()),
+                    ("error", components.schema::<String>()),
+                    ("revertReason", components.schema::<String>()),
+                    ("calls", components.schema::<Vec<CallFrame>>()),
+                    ("logs", components.schema::<Vec<CallLogFrame>>()),
+                    ("value", components.schema::<Quantity>()),
+                ],
+                &["type", "from", "gas", "gasUsed", "input"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for DebugTrace {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("DebugTrace", |components| {
+            json!({
+                "title": "DebugTrace",
+                "anyOf": [
+                    components.schema::<StructLogTrace>(),
+                    components.schema::<CallFrame>(),
+                    components.schema::<Value>(),
+                ],
+            })
+        })
+    }
+}
+
+impl OpenRpcSchema for BlockTraceResult {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockTraceResult", |components| {
+            object_schema(
+                "BlockTraceResult",
+                vec![
+                    ("txHash", components.schema::<Data32>()),
+                    ("result", components.schema::<DebugTrace>()),
+                    ("error", components.schema::<String>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracer_config() {
+        let config: TracerConfig = serde_json::from_value(json!({
+            "tracer": "callTracer",
+            "tracerConfig": {"onlyTopCall": true},
+            "timeout": "10s"
+        }))
+        .unwrap();
+        assert_eq!(config.tracer, Some(Tracer::CallTracer));
+        assert!(config.call_tracer_config().unwrap().only_top_call);
+        assert!(!config.enable_memory);
+
+        assert_eq!(
+            serde_json::to_value(TracerConfig::default()).unwrap(),
+            json!({})
+        );
+        let config = TracerConfig {
+            tracer_config: Some(json!({"onlyTopCall": "yes"})),
+            ..Default::default()
+        };
+        assert!(config.call_tracer_config().is_err());
+    }
+
+    #[test]
+    fn test_other_tracers() {
+        let json = json!({
+            "tracer": "prestateTracer",
+            "tracerConfig": {"diffMode": true}
+        });
+        let config: TracerConfig = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(config.tracer, Some(Tracer::PrestateTracer));
+        assert_eq!(serde_json::to_value(&config).unwrap(), json);
+
+        let config: TracerConfig =
+            serde_json::from_value(json!({"tracer": "4byteTracer"})).unwrap();
+        assert_eq!(config.tracer, Some(Tracer::FourByteTracer));
+
+        let code = "{data: [], fault: function() {}, step: function() {}, result: function() { return this.data; }}";
+        let config: TracerConfig = serde_json::from_value(json!({"tracer": code})).unwrap();
+        assert_eq!(config.tracer, Some(Tracer::Other(code.to_owned())));
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({"tracer": code})
+        );
+
+        let output = json!({"0x27dc297e-128": 1});
+        let parsed: DebugTrace = serde_json::from_value(output.clone()).unwrap();
+        assert_eq!(parsed, DebugTrace::Other(output.clone()));
+        assert_eq!(serde_json::to_value(parsed).unwrap(), output);
+    }
+
+    #[test]
+    fn test_struct_logs() {
+        let trace = json!({
+            "failed": false,
+            "gas": 21012,
+            "returnValue": "",
+            "structLogs": [{
+                "pc": 0,
+                "op": "PUSH1",
+                "gas": 978,
+                "gasCost": 3,
+                "depth": 1,
+                "stack": [],
+                "memory": [],
+                "storage": {}
+            }, {
+                "pc": 2,
+                "op": "SSTORE",
+                "gas": 975,
+                "gasCost": 20000,
+                "depth": 1,
+                "stack": ["0x60"],
+                "storage": {
+                    "0000000000000000000000000000000000000000000000000000000000000000":
+                        "0000000000000000000000000000000000000000000000000000000000000060"
+                },
+                "error": "out of gas"
+            }]
+        });
+        let parsed: DebugTrace = serde_json::from_value(trace.clone()).unwrap();
+        match parsed {
+            DebugTrace::StructLogs(ref logs) => {
+                assert_eq!(logs.struct_logs[1].op, "SSTORE");
+                assert_eq!(logs.struct_logs[1].stack, Some(vec![U256::from(0x60)]));
+            }
+            _ => panic!("should be struct logs"),
+        }
+        assert_eq!(serde_json::to_value(parsed).unwrap(), trace);
+    }
+
+    #[test]
+    fn test_call_frame() {
+        let trace = json!({
+            "type": "CALL",
+            "from": "0x000000000000000000000000000000000000000a",
+            "gas": "0x5208",
+            "gasUsed": "0x5208",
+            "to": "0x000000000000000000000000000000000000000b",
+            "input": "0x",
+            "calls": [{
+                "type": "STATICCALL",
+                "from": "0x000000000000000000000000000000000000000b",
+                "gas": "0x100",
+                "gasUsed": "0x10",
+                "to": "0x000000000000000000000000000000000000000c",
+                "input": "0x01",
+                "output": "0x02",
+                "error": "execution reverted",
+                "revertReason": "not allowed"
+            }],
+            "value": "0x1"
+        });
+        let parsed: DebugTrace = serde_json::from_value(trace.clone()).unwrap();
+        match parsed {
+            DebugTrace::CallTracer(ref frame) => {
+                assert_eq!(frame.calls.len(), 1);
+                assert_eq!(frame.calls[0].type_, "STATICCALL");
+                assert_eq!(frame.calls[0].revert_reason.as_deref(), Some("not allowed"));
+            }
+            _ => panic!("should be a call frame"),
+        }
+        assert_eq!(serde_json::to_value(parsed).unwrap(), trace);
+    }
+}
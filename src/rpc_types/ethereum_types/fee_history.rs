@@ -0,0 +1,405 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::ethereum_types::{
+    EthBlock, EthBlockHeader, EthBlockTransaction, EthReceipt, EthRpcTransaction,
+};
+use crate::rpc_types::Quantity;
+use crate::Error;
+use ethereum_types::{U256, U512};
+use serde_json::Value;
+use std::convert::TryFrom;
+
+/// The maximum number of blocks of one `eth_feeHistory` request.
+pub const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+
+// Parameters of EIP-1559.
+const ELASTICITY_MULTIPLIER: u64 = 2;
+const BASE_FEE_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Result of `eth_feeHistory`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    pub oldest_block: U256,
+    /// The base fees of the blocks, and of the block after the newest one.
+    pub base_fee_per_gas: Vec<U256>,
+    pub gas_used_ratio: Vec<f64>,
+    /// The priority fees at the requested percentiles of every block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+}
+
+/// Check the block count and resolve the range of blocks, returns the oldest
+/// block and the number of blocks, which is less than the count if it reaches
+/// beyond the genesis block.
+pub fn fee_history_range(block_count: u64, newest_block: u64) -> Result<(u64, u64), Error> {
+    check_block_count(block_count)?;
+    if block_count == 0 {
+        return Ok((newest_block.saturating_add(1), 0));
+    }
+    // `newest_block + 1` may overflow, the blocks before the newest one are
+    // counted instead.
+    let block_count = (block_count - 1).min(newest_block) + 1;
+    Ok((newest_block - (block_count - 1), block_count))
+}
+
+fn check_block_count(block_count: u64) -> Result<(), Error> {
+    if block_count > MAX_FEE_HISTORY_BLOCKS {
+        Err(Error::invalid_params(format!(
+            "block count should be at most {}",
+            MAX_FEE_HISTORY_BLOCKS
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// The percentiles should be in `0..=100` and monotonically increasing.
+pub fn validate_reward_percentiles(percentiles: &[f64]) -> Result<(), Error> {
+    let mut last = 0.0;
+    for percentile in percentiles {
+        if !(0.0..=100.0).contains(percentile) {
+            return Err(Error::invalid_params(format!(
+                "reward percentile {} should be in [0, 100]",
+                percentile
+            )));
+        }
+        if *percentile < last {
+            return Err(Error::invalid_params(
+                "reward percentiles should be monotonically increasing",
+            ));
+        }
+        last = *percentile;
+    }
+    Ok(())
+}
+
+/// The base fee of the block after the given one, see EIP-1559.
+pub fn next_base_fee(header: &EthBlockHeader) -> U256 {
+    let base_fee = header.base_fee_per_gas;
+    let gas_target = header.gas_limit / ELASTICITY_MULTIPLIER;
+    if gas_target.is_zero() || header.gas_used == gas_target {
+        return base_fee;
+    }
+    if header.gas_used > gas_target {
+        let delta = base_fee_delta(base_fee, header.gas_used - gas_target, gas_target);
+        base_fee.saturating_add(delta.max(U256::one()))
+    } else {
+        let delta = base_fee_delta(base_fee, gas_target - header.gas_used, gas_target);
+        base_fee.saturating_sub(delta)
+    }
+}
+
+// `base_fee * gas_delta / gas_target / 8`, which is computed in 512 bits, so
+// a header of any values can't overflow it, and saturated to 256 bits.
+fn base_fee_delta(base_fee: U256, gas_delta: U256, gas_target: U256) -> U256 {
+    let delta = base_fee.full_mul(gas_delta)
+        / U512::from(gas_target)
+        / U512::from(BASE_FEE_CHANGE_DENOMINATOR);
+    U256::try_from(delta).unwrap_or(U256::MAX)
+}
+
+// The priority fee paid to the miner per gas.
+fn effective_reward(
+    transaction: Option<&EthRpcTransaction>,
+    receipt: &EthReceipt,
+    base_fee: U256,
+) -> U256 {
+    match transaction {
+        Some(tx) => match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+            (Some(max_fee), Some(max_priority_fee)) => {
+                max_priority_fee.min(max_fee.saturating_sub(base_fee))
+            }
+            _ => tx.gas_price.saturating_sub(base_fee),
+        },
+        None => receipt.effective_gas_price.saturating_sub(base_fee),
+    }
+}
+
+// The rewards at the percentiles, weighted by the gas used, the same as geth.
+fn block_rewards(block: &EthBlock, receipts: &[EthReceipt], percentiles: &[f64]) -> Vec<U256> {
+    let base_fee = block.header.base_fee_per_gas;
+    let mut rewards: Vec<(U256, U256)> = receipts
+        .iter()
+        .map(|receipt| {
+            let transaction = block.transactions.iter().find_map(|tx| match tx {
+                EthBlockTransaction::Full(tx) if tx.hash == receipt.transaction_hash => Some(tx),
+                _ => None,
+            });
+            (
+                effective_reward(transaction, receipt, base_fee),
+                receipt.gas_used,
+            )
+        })
+        .collect();
+    if rewards.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+    rewards.sort_by_key(|(reward, _)| *reward);
+
+    let total_gas_used = rewards
+        .iter()
+        .fold(U256::zero(), |total, (_, gas_used)| total + gas_used);
+    let mut index = 0;
+    let mut sum_gas_used = rewards[0].1;
+    percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = u256_to_f64(total_gas_used) * percentile / 100.0;
+            while u256_to_f64(sum_gas_used) < threshold && index < rewards.len() - 1 {
+                index += 1;
+                sum_gas_used += rewards[index].1;
+            }
+            rewards[index].0
+        })
+        .collect()
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.0.iter().rev().fold(0.0, |acc, word| {
+        acc * 18_446_744_073_709_551_616.0 + *word as f64
+    })
+}
+
+impl FeeHistory {
+    /// Calculate the fee history of the consecutive blocks, ordered by the
+    /// height, each of them with the receipts of its transactions.
+    pub fn calculate(
+        blocks: &[(EthBlock, Vec<EthReceipt>)],
+        reward_percentiles: &[f64],
+    ) -> Result<Self, Error> {
+        check_block_count(blocks.len() as u64)?;
+        validate_reward_percentiles(reward_percentiles)?;
+        let (first, last) = match (blocks.first(), blocks.last()) {
+            (Some((first, _)), Some((last, _))) => (first, last),
+            _ => return Ok(FeeHistory::default()),
+        };
+
+        let mut base_fee_per_gas: Vec<U256> = blocks
+            .iter()
+            .map(|(block, _)| block.header.base_fee_per_gas)
+            .collect();
+        base_fee_per_gas.push(next_base_fee(&last.header));
+        let gas_used_ratio = blocks
+            .iter()
+            .map(|(block, _)| {
+                if block.header.gas_limit.is_zero() {
+                    0.0
+                } else {
+                    u256_to_f64(block.header.gas_used) / u256_to_f64(block.header.gas_limit)
+                }
+            })
+            .collect();
+        let reward = if reward_percentiles.is_empty() {
+            None
+        } else {
+            Some(
+                blocks
+                    .iter()
+                    .map(|(block, receipts)| block_rewards(block, receipts, reward_percentiles))
+                    .collect(),
+            )
+        };
+        Ok(FeeHistory {
+            oldest_block: first.header.number,
+            base_fee_per_gas,
+            gas_used_ratio,
+            reward,
+        })
+    }
+}
+
+impl OpenRpcSchema for FeeHistory {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("FeeHistory", |components| {
+            object_schema(
+                "FeeHistory",
+                vec![
+                    ("oldestBlock", components.schema::<Quantity>()),
+                    ("baseFeePerGas", components.schema::<Vec<Quantity>>()),
+                    ("gasUsedRatio", components.schema::<Vec<f64>>()),
+                    ("reward", components.schema::<Vec<Vec<Quantity>>>()),
+                ],
+                &["oldestBlock", "baseFeePerGas", "gasUsedRatio"],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_types::Data;
+    use ethereum_types::{Bloom, H160, H256, U64};
+
+    fn block(
+        number: u64,
+        base_fee: u64,
+        gas_used: u64,
+        txs: &[(u64, u64)],
+    ) -> (EthBlock, Vec<EthReceipt>) {
+        let header = EthBlockHeader {
+            parent_hash: H256::from_low_u64_be(number.saturating_sub(1)),
+            sha3_uncles: H256::zero(),
+            miner: H160::zero(),
+            state_root: H256::zero(),
+            transactions_root: H256::zero(),
+            receipts_root: H256::zero(),
+            logs_bloom: Bloom::zero(),
+            difficulty: U256::zero(),
+            number: number.into(),
+            gas_limit: 1_000_000u64.into(),
+            gas_used: gas_used.into(),
+            timestamp: U256::zero(),
+            extra_data: Data::new(vec![]),
+            mix_hash: H256::zero(),
+            nonce: Default::default(),
+            base_fee_per_gas: base_fee.into(),
+            hash: H256::from_low_u64_be(number),
+            total_difficulty: U256::zero(),
+        };
+        // (priority fee, gas used) of the transactions
+        let (transactions, receipts) = txs
+            .iter()
+            .enumerate()
+            .map(|(index, (priority_fee, gas_used))| {
+                let hash = H256::from_low_u64_be(number * 100 + index as u64);
+                let tx = EthRpcTransaction {
+                    hash,
+                    max_fee_per_gas: Some((base_fee + priority_fee * 2).into()),
+                    max_priority_fee_per_gas: Some((*priority_fee).into()),
+                    ..Default::default()
+                };
+                let receipt = EthReceipt {
+                    block_hash: H256::from_low_u64_be(number),
+                    block_number: U64::from(number),
+                    transaction_hash: hash,
+                    transaction_index: U64::from(index),
+                    from: H160::zero(),
+                    to: None,
+                    gas_used: (*gas_used).into(),
+                    cumulative_gas_used: U256::zero(),
+                    contract_address: None,
+                    logs: vec![],
+                    logs_bloom: Bloom::zero(),
+                    type_: U64::from(2),
+                    effective_gas_price: U256::zero(),
+                    root: None,
+                    status: None,
+                };
+                (EthBlockTransaction::Full(tx), receipt)
+            })
+            .unzip();
+        let block = EthBlock {
+            header,
+            size: U256::zero(),
+            transactions,
+            uncles: vec![],
+        };
+        (block, receipts)
+    }
+
+    #[test]
+    fn test_fee_history_calculate() {
+        let blocks = vec![
+            block(
+                10,
+                1000,
+                500_000,
+                &[(30, 100_000), (10, 300_000), (20, 100_000)],
+            ),
+            block(11, 1000, 750_000, &[]),
+        ];
+        let history = FeeHistory::calculate(&blocks, &[0.0, 50.0, 80.0, 100.0]).unwrap();
+        assert_eq!(history.oldest_block, 10u64.into());
+        assert_eq!(
+            history.base_fee_per_gas,
+            vec![1000u64.into(), 1000u64.into(), 1062u64.into()]
+        );
+        assert_eq!(history.gas_used_ratio, vec![0.5, 0.75]);
+        assert_eq!(
+            history.reward,
+            Some(vec![
+                vec![10u64.into(), 10u64.into(), 20u64.into(), 30u64.into()],
+                vec![U256::zero(); 4],
+            ])
+        );
+
+        let history = FeeHistory::calculate(&blocks, &[]).unwrap();
+        assert_eq!(history.reward, None);
+        let value = serde_json::to_value(&history).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "oldestBlock": "0xa",
+                "baseFeePerGas": ["0x3e8", "0x3e8", "0x426"],
+                "gasUsedRatio": [0.5, 0.75],
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<FeeHistory>(value).unwrap(),
+            history
+        );
+
+        assert_eq!(
+            FeeHistory::calculate(&[], &[]).unwrap(),
+            FeeHistory::default()
+        );
+    }
+
+    #[test]
+    fn test_fee_history_validation() {
+        assert!(validate_reward_percentiles(&[0.0, 25.0, 25.0, 100.0]).is_ok());
+        assert!(validate_reward_percentiles(&[50.0, 25.0]).is_err());
+        assert!(validate_reward_percentiles(&[-1.0]).is_err());
+        assert!(validate_reward_percentiles(&[100.5]).is_err());
+
+        assert_eq!(fee_history_range(4, 10).unwrap(), (7, 4));
+        assert_eq!(fee_history_range(4, 2).unwrap(), (0, 3));
+        assert_eq!(fee_history_range(0, 2).unwrap(), (3, 0));
+        assert!(fee_history_range(MAX_FEE_HISTORY_BLOCKS, 10_000).is_ok());
+        assert!(fee_history_range(MAX_FEE_HISTORY_BLOCKS + 1, 10_000).is_err());
+        assert_eq!(
+            fee_history_range(1, u64::MAX - 1).unwrap(),
+            (u64::MAX - 1, 1)
+        );
+        assert_eq!(fee_history_range(1, u64::MAX).unwrap(), (u64::MAX, 1));
+        assert_eq!(fee_history_range(4, u64::MAX).unwrap(), (u64::MAX - 3, 4));
+        assert_eq!(fee_history_range(4, 0).unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn test_next_base_fee() {
+        let (full, _) = block(1, 1000, 1_000_000, &[]);
+        assert_eq!(next_base_fee(&full.header), 1125u64.into());
+        let (empty, _) = block(1, 1000, 0, &[]);
+        assert_eq!(next_base_fee(&empty.header), 875u64.into());
+        let (target, _) = block(1, 1000, 500_000, &[]);
+        assert_eq!(next_base_fee(&target.header), 1000u64.into());
+        let (cita, _) = block(1, 0, 1_000_000, &[]);
+        assert_eq!(next_base_fee(&cita.header), 1u64.into());
+
+        // The values of a corrupt header don't overflow.
+        let (mut corrupt, _) = block(1, 1000, 0, &[]);
+        corrupt.header.base_fee_per_gas = U256::MAX;
+        corrupt.header.gas_limit = U256::from(2);
+        corrupt.header.gas_used = U256::MAX;
+        assert_eq!(next_base_fee(&corrupt.header), U256::MAX);
+        corrupt.header.gas_limit = U256::MAX;
+        corrupt.header.gas_used = U256::zero();
+        assert_eq!(next_base_fee(&corrupt.header), U256::MAX - U256::MAX / 8);
+    }
+}
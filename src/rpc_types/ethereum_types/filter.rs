@@ -13,11 +13,12 @@
 // limitations under the License.
 
 use serde::ser::Serialize;
-use serde::{Deserialize, Serializer};
-use serde_json::Value;
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_json::{json, Value};
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::ethereum_types::EthLog;
-use crate::rpc_types::{BlockNumber, Data20, Data32, VariadicValue};
+use crate::rpc_types::{BlockNumber, Changes, Data20, Data32, FilterChanges, VariadicValue};
 
 /// Filter Address
 pub type FilterAddress = VariadicValue<Data20>;
@@ -64,6 +65,44 @@ impl EthFilter {
     }
 }
 
+impl EthFilter {
+    /// Whether the log matches the address and topics of the filter, the block
+    /// range is not checked.
+    pub fn matches(&self, log: &EthLog) -> bool {
+        let address = Data20::new(log.address);
+        let address_matched = match self.address {
+            None | Some(VariadicValue::Null) => true,
+            Some(VariadicValue::Single(ref expected)) => *expected == address,
+            Some(VariadicValue::Multiple(ref expected)) => {
+                expected.is_empty() || expected.contains(&address)
+            }
+        };
+        if !address_matched {
+            return false;
+        }
+        let topics = match self.topics {
+            Some(ref topics) => topics,
+            None => return true,
+        };
+        if topics.len() > log.topics.len() {
+            return false;
+        }
+        topics
+            .iter()
+            .zip(log.topics.iter())
+            .all(|(expected, topic)| {
+                let topic = Data32::new(*topic);
+                match expected {
+                    VariadicValue::Null => true,
+                    VariadicValue::Single(expected) => *expected == topic,
+                    VariadicValue::Multiple(expected) => {
+                        expected.is_empty() || expected.contains(&topic)
+                    }
+                }
+            })
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum EthFilterChanges {
     /// New logs.
@@ -86,3 +125,190 @@ impl Serialize for EthFilterChanges {
         }
     }
 }
+
+impl<'de> Deserialize<'de> for EthFilterChanges {
+    fn deserialize<D>(deserializer: D) -> Result<EthFilterChanges, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match Changes::deserialize(deserializer)? {
+            Changes::Logs(logs) => EthFilterChanges::Logs(logs),
+            Changes::Hashes(hashes) => EthFilterChanges::Hashes(hashes),
+            Changes::Empty => EthFilterChanges::Empty,
+        })
+    }
+}
+
+impl From<FilterChanges> for EthFilterChanges {
+    fn from(changes: FilterChanges) -> Self {
+        match changes {
+            FilterChanges::Logs(logs) => {
+                EthFilterChanges::Logs(logs.into_iter().map(EthLog::from).collect())
+            }
+            FilterChanges::Hashes(hashes) => EthFilterChanges::Hashes(hashes),
+            FilterChanges::Empty => EthFilterChanges::Empty,
+        }
+    }
+}
+
+impl OpenRpcSchema for EthFilter {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthFilter", |components| {
+            object_schema(
+                "EthFilter",
+                vec![
+                    ("fromBlock", components.schema::<BlockNumber>()),
+                    ("toBlock", components.schema::<BlockNumber>()),
+                    ("address", components.schema::<FilterAddress>()),
+                    ("topics", components.schema::<Vec<Topic>>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for EthFilterChanges {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthFilterChanges", |components| {
+            json!({
+                "title": "EthFilterChanges",
+                "oneOf": [
+                    components.schema::<Vec<EthLog>>(),
+                    components.schema::<Vec<Data32>>(),
+                ],
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EthFilter, EthFilterChanges, EthLog};
+    use crate::rpc_types::{BlockNumber, Data, Data20, Data32, FilterChanges, Log, VariadicValue};
+    use ethereum_types::{H160, H256, U256};
+
+    #[test]
+    fn test_eth_filter_changes_serde() {
+        assert_eq!(
+            EthFilterChanges::Empty,
+            serde_json::from_str::<EthFilterChanges>("[]").unwrap()
+        );
+        assert_eq!(
+            EthFilterChanges::Empty,
+            serde_json::from_str::<EthFilterChanges>("null").unwrap()
+        );
+        assert!(serde_json::from_str::<EthFilterChanges>("1").is_err());
+        assert!(serde_json::from_str::<EthFilterChanges>("[1]").is_err());
+        assert!(serde_json::from_value::<EthFilterChanges>(json!([
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            {"address": "0x0000000000000000000000000000000000000010"}
+        ]))
+        .is_err());
+
+        let hashes = EthFilterChanges::Hashes(vec![Data32::new(H256::from_low_u64_be(1))]);
+        let value = serde_json::to_value(&hashes).unwrap();
+        assert_eq!(
+            value,
+            json!(["0x0000000000000000000000000000000000000000000000000000000000000001"])
+        );
+        assert_eq!(hashes, serde_json::from_value(value).unwrap());
+
+        let logs = EthFilterChanges::Logs(vec![EthLog {
+            address: H160::from_low_u64_be(16),
+            topics: vec![H256::from_low_u64_be(2)],
+            data: Data::new(vec![1, 2]),
+            block_hash: H256::from_low_u64_be(3),
+            block_number: 4u64.into(),
+            transaction_hash: H256::from_low_u64_be(5),
+            transaction_index: 6u64.into(),
+            log_index: U256::from(7),
+            removed: false,
+        }]);
+        let value = serde_json::to_value(&logs).unwrap();
+        assert_eq!(value[0]["blockNumber"], json!("0x4"));
+        assert_eq!(logs, serde_json::from_value(value).unwrap());
+    }
+
+    #[test]
+    fn test_eth_filter_matches() {
+        let log = EthLog {
+            address: H160::from_low_u64_be(16),
+            topics: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+            data: Data::new(vec![]),
+            block_hash: H256::zero(),
+            block_number: 1u64.into(),
+            transaction_hash: H256::zero(),
+            transaction_index: 0u64.into(),
+            log_index: U256::zero(),
+            removed: false,
+        };
+        let address = |n| Data20::new(H160::from_low_u64_be(n));
+        let topic = |n| Data32::new(H256::from_low_u64_be(n));
+        let filter = |address, topics| {
+            EthFilter::new(
+                BlockNumber::latest(),
+                BlockNumber::latest(),
+                address,
+                topics,
+            )
+        };
+
+        assert!(filter(None, None).matches(&log));
+        assert!(filter(Some(VariadicValue::single(address(16))), None).matches(&log));
+        assert!(!filter(Some(VariadicValue::single(address(17))), None).matches(&log));
+        assert!(filter(
+            Some(VariadicValue::multiple(vec![address(17), address(16)])),
+            None
+        )
+        .matches(&log));
+        assert!(filter(
+            None,
+            Some(vec![VariadicValue::null(), VariadicValue::single(topic(2))])
+        )
+        .matches(&log));
+        assert!(filter(
+            None,
+            Some(vec![VariadicValue::multiple(vec![topic(3), topic(1)])])
+        )
+        .matches(&log));
+        assert!(!filter(None, Some(vec![VariadicValue::single(topic(2))])).matches(&log));
+        assert!(!filter(
+            None,
+            Some(vec![
+                VariadicValue::null(),
+                VariadicValue::null(),
+                VariadicValue::null()
+            ])
+        )
+        .matches(&log));
+    }
+
+    #[test]
+    fn test_eth_filter_changes_from_native() {
+        let log = Log {
+            address: H160::from_low_u64_be(16),
+            topics: vec![H256::from_low_u64_be(2)],
+            data: Data::new(vec![1, 2]),
+            block_hash: Some(H256::from_low_u64_be(3)),
+            block_number: Some(U256::from(4)),
+            transaction_hash: Some(H256::from_low_u64_be(5)),
+            transaction_index: Some(U256::from(6)),
+            log_index: Some(U256::from(7)),
+            transaction_log_index: Some(U256::from(0)),
+        };
+        assert_eq!(
+            EthFilterChanges::from(FilterChanges::Logs(vec![log.clone()])),
+            EthFilterChanges::Logs(vec![EthLog::from(log)])
+        );
+        let hashes = vec![Data32::new(H256::from_low_u64_be(1))];
+        assert_eq!(
+            EthFilterChanges::from(FilterChanges::Hashes(hashes.clone())),
+            EthFilterChanges::Hashes(hashes)
+        );
+        assert_eq!(
+            EthFilterChanges::from(FilterChanges::Empty),
+            EthFilterChanges::Empty
+        );
+    }
+}
@@ -0,0 +1,417 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data;
+use crate::rpc_types::{Data20, Data32, Quantity};
+use crate::Error;
+use ethereum_types::{Address, H256, U256, U64};
+use keccak_hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use serde_json::Value;
+
+/// Result of `eth_getProof`, see EIP-1186.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthAccountProof {
+    pub address: Address,
+    pub account_proof: Vec<Data>,
+    pub balance: U256,
+    pub code_hash: H256,
+    pub nonce: U64,
+    pub storage_hash: H256,
+    pub storage_proof: Vec<EthStorageProof>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EthStorageProof {
+    pub key: H256,
+    pub value: U256,
+    pub proof: Vec<Data>,
+}
+
+/// The state proof returned by `GetStateProof` of CITA, which is the RLP list
+/// `[address, account_proof, key, value_proof]`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StateProof {
+    pub address: Address,
+    pub account_proof: Vec<Vec<u8>>,
+    pub key: H256,
+    pub value_proof: Vec<Vec<u8>>,
+}
+
+impl StateProof {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        rlp::decode(bytes).map_err(|err| Error::invalid_state_proof(format!("{:?}", err)))
+    }
+}
+
+impl Encodable for StateProof {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.address);
+        s.append_list::<Vec<u8>, Vec<u8>>(&self.account_proof);
+        s.append(&self.key);
+        s.append_list::<Vec<u8>, Vec<u8>>(&self.value_proof);
+    }
+}
+
+impl Decodable for StateProof {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 4 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(StateProof {
+            address: rlp.val_at(0)?,
+            account_proof: rlp.list_at(1)?,
+            key: rlp.val_at(2)?,
+            value_proof: rlp.list_at(3)?,
+        })
+    }
+}
+
+// Decode the hex-prefix encoded path of a leaf or an extension node, returns
+// the nibbles and whether it is a leaf.
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(Vec<u8>, bool), DecoderError> {
+    let (first, rest) = encoded
+        .split_first()
+        .ok_or(DecoderError::Custom("empty path of trie node"))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(DecoderError::Custom("invalid path of trie node"));
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(rest.iter().flat_map(|b| [b >> 4, b & 0x0f]));
+    Ok((nibbles, flag >= 2))
+}
+
+// Look up the value of the key in the proof of a secure trie, by walking the
+// nodes from the root along `keccak(key)`. The hashes of the nodes are not
+// checked, which is up to the verifier of the proof.
+fn proof_lookup(proof: &[Vec<u8>], key: &[u8]) -> Result<Option<Vec<u8>>, DecoderError> {
+    let path: Vec<u8> = keccak(key)
+        .as_bytes()
+        .iter()
+        .flat_map(|b| [b >> 4, b & 0x0f])
+        .collect();
+    let mut path = &path[..];
+    let mut nodes = proof.iter();
+    let mut node = match nodes.next() {
+        Some(node) => Rlp::new(node),
+        None => return Ok(None),
+    };
+    loop {
+        match node.item_count()? {
+            0 => return Ok(None),
+            2 => {
+                let (partial, is_leaf) = decode_hex_prefix(node.at(0)?.data()?)?;
+                if is_leaf {
+                    return if path == &partial[..] {
+                        Ok(Some(node.at(1)?.data()?.to_vec()))
+                    } else {
+                        Ok(None)
+                    };
+                }
+                if !path.starts_with(&partial) {
+                    return Ok(None);
+                }
+                path = &path[partial.len()..];
+                let child = node.at(1)?;
+                if child.is_list() {
+                    node = child;
+                    continue;
+                }
+            }
+            17 => {
+                if path.is_empty() {
+                    let value = node.at(16)?.data()?;
+                    return Ok(Some(value.to_vec()).filter(|value| !value.is_empty()));
+                }
+                let child = node.at(path[0] as usize)?;
+                path = &path[1..];
+                if child.is_empty() {
+                    return Ok(None);
+                }
+                if child.is_list() {
+                    node = child;
+                    continue;
+                }
+            }
+            _ => return Err(DecoderError::RlpIncorrectListLen),
+        }
+        node = match nodes.next() {
+            Some(node) => Rlp::new(node),
+            None => return Err(DecoderError::Custom("incomplete proof")),
+        };
+    }
+}
+
+impl EthStorageProof {
+    /// Convert the value proof of a CITA state proof, the value is decoded from
+    /// the proof, and is zero if the key is not in the storage.
+    pub fn from_state_proof(proof: &StateProof) -> Result<Self, Error> {
+        let invalid = |err: DecoderError| Error::invalid_state_proof(format!("{:?}", err));
+        let value = match proof_lookup(&proof.value_proof, proof.key.as_bytes()).map_err(invalid)? {
+            Some(value) => rlp::decode(&value).map_err(invalid)?,
+            None => U256::zero(),
+        };
+        Ok(EthStorageProof {
+            key: proof.key,
+            value,
+            proof: proof.value_proof.iter().cloned().map(Data::new).collect(),
+        })
+    }
+}
+
+impl EthAccountProof {
+    /// Convert the CITA state proofs of an address. The account is decoded
+    /// from the account proof of `account`, and every one of `storage` is
+    /// converted to a storage proof.
+    ///
+    /// The account of CITA has more fields than the one of Ethereum, only the
+    /// first four (nonce, balance, storage root and code hash) are used.
+    pub fn from_state_proofs(account: &StateProof, storage: &[StateProof]) -> Result<Self, Error> {
+        let invalid = |err: DecoderError| Error::invalid_state_proof(format!("{:?}", err));
+        let (nonce, balance, storage_hash, code_hash) =
+            match proof_lookup(&account.account_proof, account.address.as_bytes())
+                .map_err(invalid)?
+            {
+                Some(value) => {
+                    let rlp = Rlp::new(&value);
+                    (
+                        rlp.val_at(0).map_err(invalid)?,
+                        rlp.val_at(1).map_err(invalid)?,
+                        rlp.val_at(2).map_err(invalid)?,
+                        rlp.val_at(3).map_err(invalid)?,
+                    )
+                }
+                None => (U64::zero(), U256::zero(), KECCAK_NULL_RLP, KECCAK_EMPTY),
+            };
+        let storage_proof = storage
+            .iter()
+            .map(|proof| {
+                if proof.address == account.address {
+                    EthStorageProof::from_state_proof(proof)
+                } else {
+                    Err(Error::invalid_state_proof("address mismatched"))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(EthAccountProof {
+            address: account.address,
+            account_proof: account
+                .account_proof
+                .iter()
+                .cloned()
+                .map(Data::new)
+                .collect(),
+            balance,
+            code_hash,
+            nonce,
+            storage_hash,
+            storage_proof,
+        })
+    }
+}
+
+impl OpenRpcSchema for EthAccountProof {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthAccountProof", |components| {
+            object_schema(
+                "EthAccountProof",
+                vec![
+                    ("address", components.schema::<Data20>()),
+                    ("accountProof", components.schema::<Vec<Data>>()),
+                    ("balance", components.schema::<Quantity>()),
+                    ("codeHash", components.schema::<Data32>()),
+                    ("nonce", components.schema::<Quantity>()),
+                    ("storageHash", components.schema::<Data32>()),
+                    ("storageProof", components.schema::<Vec<EthStorageProof>>()),
+                ],
+                &[
+                    "address",
+                    "accountProof",
+                    "balance",
+                    "codeHash",
+                    "nonce",
+                    "storageHash",
+                    "storageProof",
+                ],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for EthStorageProof {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthStorageProof", |components| {
+            object_schema(
+                "EthStorageProof",
+                vec![
+                    ("key", components.schema::<Data32>()),
+                    ("value", components.schema::<Quantity>()),
+                    ("proof", components.schema::<Vec<Data>>()),
+                ],
+                &["key", "value", "proof"],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_types::H160;
+
+    fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+        let flag = if is_leaf { 2 } else { 0 };
+        let mut encoded = if nibbles.len() % 2 == 1 {
+            vec![((flag + 1) << 4) | nibbles[0]]
+        } else {
+            vec![flag << 4]
+        };
+        let rest = &nibbles[nibbles.len() % 2..];
+        encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+        encoded
+    }
+
+    fn nibbles(key: &[u8]) -> Vec<u8> {
+        keccak(key)
+            .as_bytes()
+            .iter()
+            .flat_map(|b| [b >> 4, b & 0x0f])
+            .collect()
+    }
+
+    fn leaf(nibbles: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut s = RlpStream::new_list(2);
+        s.append(&hex_prefix(nibbles, true));
+        s.append(&value.to_vec());
+        s.out().to_vec()
+    }
+
+    // A proof of a branch node as the root, and the leaf of the key under it.
+    fn branch_proof(key: &[u8], value: &[u8]) -> Vec<Vec<u8>> {
+        let path = nibbles(key);
+        let leaf = leaf(&path[1..], value);
+        let mut s = RlpStream::new_list(17);
+        for index in 0..16u8 {
+            if index == path[0] {
+                s.append(&keccak(&leaf));
+            } else {
+                s.append_empty_data();
+            }
+        }
+        s.append_empty_data();
+        vec![s.out().to_vec(), leaf]
+    }
+
+    fn account(nonce: u64, balance: u64, storage_root: H256) -> Vec<u8> {
+        let mut s = RlpStream::new_list(5);
+        s.append(&U64::from(nonce));
+        s.append(&U256::from(balance));
+        s.append(&storage_root);
+        s.append(&keccak([0x60u8, 0x00]));
+        // abi hash of CITA
+        s.append(&KECCAK_EMPTY);
+        s.out().to_vec()
+    }
+
+    #[test]
+    fn test_state_proof_rlp() {
+        let proof = StateProof {
+            address: H160::from_low_u64_be(1),
+            account_proof: vec![vec![1, 2], vec![3]],
+            key: H256::from_low_u64_be(2),
+            value_proof: vec![vec![4]],
+        };
+        let bytes = rlp::encode(&proof);
+        assert_eq!(StateProof::from_bytes(&bytes).unwrap(), proof);
+        assert!(StateProof::from_bytes(&[0xc0]).is_err());
+    }
+
+    #[test]
+    fn test_account_proof_from_state_proofs() {
+        let address = H160::from_low_u64_be(0x10);
+        let key = H256::from_low_u64_be(1);
+        let storage_root = H256::from_low_u64_be(0xff);
+        let proof = StateProof {
+            address,
+            account_proof: branch_proof(address.as_bytes(), &account(3, 100, storage_root)),
+            key,
+            value_proof: vec![leaf(
+                &nibbles(key.as_bytes()),
+                &rlp::encode(&U256::from(42)),
+            )],
+        };
+        let missing = StateProof {
+            key: H256::from_low_u64_be(2),
+            ..proof.clone()
+        };
+
+        let result = EthAccountProof::from_state_proofs(&proof, &[proof.clone(), missing]).unwrap();
+        assert_eq!(result.address, address);
+        assert_eq!(result.nonce, U64::from(3));
+        assert_eq!(result.balance, U256::from(100));
+        assert_eq!(result.storage_hash, storage_root);
+        assert_eq!(result.code_hash, keccak([0x60u8, 0x00]));
+        assert_eq!(result.account_proof.len(), 2);
+        assert_eq!(result.storage_proof.len(), 2);
+        assert_eq!(result.storage_proof[0].key, key);
+        assert_eq!(result.storage_proof[0].value, U256::from(42));
+        assert_eq!(result.storage_proof[1].value, U256::zero());
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["nonce"], json!("0x3"));
+        assert_eq!(value["balance"], json!("0x64"));
+        assert_eq!(value["storageProof"][0]["value"], json!("0x2a"));
+        assert_eq!(
+            serde_json::from_value::<EthAccountProof>(value).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_account_proof_of_missing_account() {
+        let address = H160::from_low_u64_be(0x10);
+        let other = H160::from_low_u64_be(0x11);
+        let proof = StateProof {
+            address,
+            account_proof: branch_proof(other.as_bytes(), &account(1, 1, KECCAK_NULL_RLP)),
+            ..Default::default()
+        };
+        let result = EthAccountProof::from_state_proofs(&proof, &[]).unwrap();
+        assert_eq!(result.nonce, U64::zero());
+        assert_eq!(result.balance, U256::zero());
+        assert_eq!(result.storage_hash, KECCAK_NULL_RLP);
+        assert_eq!(result.code_hash, KECCAK_EMPTY);
+
+        let other = StateProof {
+            address: other,
+            ..Default::default()
+        };
+        assert!(EthAccountProof::from_state_proofs(&proof, &[other]).is_err());
+
+        let incomplete = StateProof {
+            address,
+            account_proof: branch_proof(address.as_bytes(), &account(1, 1, KECCAK_NULL_RLP))[..1]
+                .to_vec(),
+            ..Default::default()
+        };
+        assert!(EthAccountProof::from_state_proofs(&incomplete, &[]).is_err());
+    }
+}
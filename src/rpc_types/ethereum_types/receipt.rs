@@ -1,6 +1,12 @@
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::{Data, Log, Receipt};
+use crate::rpc_types::{Data20, Data32, Quantity};
+use crate::Error;
+use cita_cloud_proto::blockchain::raw_transaction;
 use cita_tool::U256;
 use ethereum_types::{Address, Bloom, H256, U64};
+use serde_json::Value;
+use std::collections::HashMap;
 
 /// Receipt
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -93,6 +99,63 @@ impl From<Receipt> for EthReceipt {
     }
 }
 
+impl EthReceipt {
+    /// Convert the receipts of a block, ordered as the transactions in it.
+    ///
+    /// The fields which need the whole block are filled as well: `from` and
+    /// `to` of the transaction, the index of the transaction, the cumulative
+    /// gas used and the index of the logs in the block. The UTXO transactions
+    /// have no receipt and are skipped, a missing receipt of the other
+    /// transactions is an error, as the following receipts would be wrong.
+    ///
+    /// The index of the transaction is its position in the block, the UTXO
+    /// transactions are counted, so it is the index the transaction is got
+    /// by with `eth_getTransactionByBlockNumberAndIndex`.
+    pub fn from_block(
+        block: cita_cloud_proto::blockchain::Block,
+        receipts: Vec<cita_cloud_proto::evm::Receipt>,
+    ) -> Result<Vec<EthReceipt>, Error> {
+        let mut receipts: HashMap<H256, EthReceipt> = receipts
+            .into_iter()
+            .map(EthReceipt::from)
+            .map(|receipt| (receipt.transaction_hash, receipt))
+            .collect();
+        let mut cumulative_gas_used = U256::zero();
+        let mut log_index = U256::zero();
+        let mut result = Vec::with_capacity(receipts.len());
+        let txs = block.body.map(|body| body.body).unwrap_or_default();
+        for (index, tx) in txs.into_iter().enumerate() {
+            let tx = match tx.tx {
+                Some(raw_transaction::Tx::NormalTx(tx)) => tx,
+                Some(raw_transaction::Tx::UtxoTx(_)) => continue,
+                None => return Err(Error::server_error(-32_000, "no transaction")),
+            };
+            let hash = H256::from_slice(&tx.transaction_hash);
+            let mut receipt = receipts
+                .remove(&hash)
+                .ok_or_else(|| Error::receipt_not_found(hash))?;
+            receipt.transaction_index = U64::from(index);
+            receipt.from = tx
+                .witness
+                .map(|witness| Address::from_slice(&witness.sender))
+                .unwrap_or_default();
+            receipt.to = tx
+                .transaction
+                .filter(|tx| tx.to.len() == 20)
+                .map(|tx| Address::from_slice(&tx.to));
+            cumulative_gas_used += receipt.gas_used;
+            receipt.cumulative_gas_used = cumulative_gas_used;
+            for log in receipt.logs.iter_mut() {
+                log.transaction_index = receipt.transaction_index;
+                log.log_index = log_index;
+                log_index += U256::one();
+            }
+            result.push(receipt);
+        }
+        Ok(result)
+    }
+}
+
 /// Log
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -143,3 +206,209 @@ impl From<Log> for EthLog {
         }
     }
 }
+
+impl OpenRpcSchema for EthReceipt {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthReceipt", |components| {
+            object_schema(
+                "EthReceipt",
+                vec![
+                    ("blockHash", components.schema::<Data32>()),
+                    ("blockNumber", components.schema::<Quantity>()),
+                    ("transactionHash", components.schema::<Data32>()),
+                    ("transactionIndex", components.schema::<Quantity>()),
+                    ("from", components.schema::<Data20>()),
+                    ("to", components.schema::<Data20>()),
+                    ("gasUsed", components.schema::<Quantity>()),
+                    ("cumulativeGasUsed", components.schema::<Quantity>()),
+                    ("contractAddress", components.schema::<Option<Data20>>()),
+                    ("logs", components.schema::<Vec<EthLog>>()),
+                    ("logsBloom", components.schema::<Data>()),
+                    ("type", components.schema::<Quantity>()),
+                    ("effectiveGasPrice", components.schema::<Quantity>()),
+                    ("root", components.schema::<Data32>()),
+                    ("status", components.schema::<Quantity>()),
+                ],
+                &[
+                    "blockHash",
+                    "blockNumber",
+                    "transactionHash",
+                    "transactionIndex",
+                    "from",
+                    "gasUsed",
+                    "cumulativeGasUsed",
+                    "contractAddress",
+                    "logs",
+                    "logsBloom",
+                    "type",
+                    "effectiveGasPrice",
+                ],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for EthLog {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthLog", |components| {
+            object_schema(
+                "EthLog",
+                vec![
+                    ("address", components.schema::<Data20>()),
+                    ("topics", components.schema::<Vec<Data32>>()),
+                    ("data", components.schema::<Data>()),
+                    ("blockHash", components.schema::<Data32>()),
+                    ("blockNumber", components.schema::<Quantity>()),
+                    ("transactionHash", components.schema::<Data32>()),
+                    ("transactionIndex", components.schema::<Quantity>()),
+                    ("logIndex", components.schema::<Quantity>()),
+                    ("removed", components.schema::<bool>()),
+                ],
+                &["address", "topics", "data", "removed"],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cita_cloud_proto::blockchain::{
+        Block, RawTransaction, RawTransactions, Transaction, UnverifiedTransaction,
+        UnverifiedUtxoTransaction, Witness,
+    };
+    use cita_cloud_proto::evm;
+
+    fn hash(n: u64) -> Vec<u8> {
+        H256::from_low_u64_be(n).as_bytes().to_vec()
+    }
+
+    fn transaction(n: u64, to: Vec<u8>) -> RawTransaction {
+        RawTransaction {
+            tx: Some(raw_transaction::Tx::NormalTx(UnverifiedTransaction {
+                transaction: Some(Transaction {
+                    to,
+                    ..Default::default()
+                }),
+                transaction_hash: hash(n),
+                witness: Some(Witness {
+                    signature: vec![0; 65],
+                    sender: Address::from_low_u64_be(n).as_bytes().to_vec(),
+                }),
+            })),
+        }
+    }
+
+    fn receipt(n: u64, quota_used: u64, logs: usize) -> evm::Receipt {
+        evm::Receipt {
+            transaction_hash: hash(n),
+            block_hash: hash(100),
+            block_number: 1,
+            quota_used: quota_used.to_be_bytes().to_vec(),
+            logs: (0..logs)
+                .map(|_| evm::Log {
+                    address: vec![0; 20],
+                    block_hash: hash(100),
+                    transaction_hash: hash(n),
+                    ..Default::default()
+                })
+                .collect(),
+            logs_bloom: vec![0; 256],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_receipts_from_block() {
+        let contract = Address::from_low_u64_be(0xff);
+        let block = Block {
+            body: Some(RawTransactions {
+                body: vec![
+                    transaction(1, contract.as_bytes().to_vec()),
+                    transaction(2, vec![]),
+                    transaction(3, contract.as_bytes().to_vec()),
+                    transaction(4, vec![]),
+                ],
+            }),
+            ..Default::default()
+        };
+        // The receipts are not ordered as the transactions.
+        let receipts = vec![
+            receipt(3, 300, 1),
+            receipt(1, 100, 2),
+            receipt(4, 400, 0),
+            receipt(2, 200, 0),
+        ];
+
+        let receipts = EthReceipt::from_block(block, receipts).unwrap();
+        assert_eq!(receipts.len(), 4);
+        let expected = [
+            (1, Some(contract), 100, 2),
+            (2, None, 300, 0),
+            (3, Some(contract), 600, 1),
+            (4, None, 1000, 0),
+        ];
+        let mut log_index = 0u64;
+        for (index, (receipt, (n, to, cumulative, logs))) in
+            receipts.iter().zip(expected.iter()).enumerate()
+        {
+            assert_eq!(receipt.transaction_hash, H256::from_low_u64_be(*n));
+            assert_eq!(receipt.transaction_index, U64::from(index));
+            assert_eq!(receipt.from, Address::from_low_u64_be(*n));
+            assert_eq!(receipt.to, *to);
+            assert_eq!(receipt.cumulative_gas_used, U256::from(*cumulative));
+            assert_eq!(receipt.logs.len(), *logs);
+            for log in receipt.logs.iter() {
+                assert_eq!(log.transaction_index, U64::from(index));
+                assert_eq!(log.log_index, U256::from(log_index));
+                log_index += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_receipts_from_block_with_utxo_transaction() {
+        let utxo = RawTransaction {
+            tx: Some(raw_transaction::Tx::UtxoTx(UnverifiedUtxoTransaction {
+                transaction_hash: hash(2),
+                ..Default::default()
+            })),
+        };
+        let block = Block {
+            body: Some(RawTransactions {
+                body: vec![transaction(1, vec![]), utxo, transaction(3, vec![])],
+            }),
+            ..Default::default()
+        };
+        let receipts = vec![receipt(1, 100, 1), receipt(3, 300, 1)];
+
+        let receipts = EthReceipt::from_block(block, receipts).unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].transaction_hash, H256::from_low_u64_be(1));
+        assert_eq!(receipts[0].transaction_index, U64::from(0));
+        // The UTXO transaction is counted in the index.
+        assert_eq!(receipts[1].transaction_hash, H256::from_low_u64_be(3));
+        assert_eq!(receipts[1].transaction_index, U64::from(2));
+        assert_eq!(receipts[1].cumulative_gas_used, U256::from(400));
+        assert_eq!(receipts[1].logs[0].log_index, U256::one());
+    }
+
+    #[test]
+    fn test_receipts_from_block_with_missing_receipt() {
+        let block = Block {
+            body: Some(RawTransactions {
+                body: vec![
+                    transaction(1, vec![]),
+                    transaction(2, vec![]),
+                    transaction(3, vec![]),
+                ],
+            }),
+            ..Default::default()
+        };
+        let receipts = vec![receipt(1, 100, 1), receipt(3, 300, 1)];
+        assert_eq!(
+            EthReceipt::from_block(block, receipts).err(),
+            Some(Error::receipt_not_found(H256::from_low_u64_be(2)))
+        );
+    }
+}
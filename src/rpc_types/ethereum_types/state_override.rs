@@ -0,0 +1,325 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The state override set and the block overrides of `eth_call`, in the
+//! format of geth.
+//!
+//! The overrides are converted into the raw bytes of CITA before they are
+//! handed to the executor, the numbers are 32 bytes in big endian.
+
+use std::collections::BTreeMap;
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data;
+use crate::rpc_types::{Data20, Data32, Quantity};
+use crate::Error;
+use ethereum_types::{Address, H256, U256, U64};
+use serde_json::{json, Value};
+
+/// Overrides of an account, `state` replaces the whole storage while
+/// `stateDiff` patches the given slots, they can not be used together.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Data>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// The state override set, the accounts to override before the call.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct StateOverride(pub BTreeMap<Address, AccountOverride>);
+
+/// Overrides of the block the call is executed in.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// Timestamp in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random: Option<H256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<U256>,
+}
+
+/// Storage override of an account for the executor.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ExecutorStorage {
+    /// Keep the storage as it is.
+    #[default]
+    Keep,
+    /// Clear the storage, then set the slots.
+    Replace(Vec<(Vec<u8>, Vec<u8>)>),
+    /// Set the slots, the others are kept.
+    Patch(Vec<(Vec<u8>, Vec<u8>)>),
+}
+
+/// An account override for the executor.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExecutorAccountOverride {
+    pub address: Vec<u8>,
+    pub balance: Option<Vec<u8>>,
+    pub nonce: Option<Vec<u8>>,
+    pub code: Option<Vec<u8>>,
+    pub storage: ExecutorStorage,
+}
+
+/// Block overrides for the executor, the timestamp is in milliseconds as
+/// the blocks of CITA.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExecutorBlockOverrides {
+    pub height: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub quota_limit: Option<u64>,
+    pub proposer: Option<Vec<u8>>,
+}
+
+fn be_bytes(value: U256) -> Vec<u8> {
+    let mut bytes = vec![0; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+fn slots(storage: &BTreeMap<H256, H256>) -> Vec<(Vec<u8>, Vec<u8>)> {
+    storage
+        .iter()
+        .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+        .collect()
+}
+
+impl AccountOverride {
+    /// The override for the executor, fails if both `state` and `stateDiff`
+    /// are set.
+    pub fn to_executor(&self, address: &Address) -> Result<ExecutorAccountOverride, Error> {
+        let storage = match (&self.state, &self.state_diff) {
+            (Some(_), Some(_)) => {
+                return Err(Error::invalid_params(format!(
+                    "account {:?} has both 'state' and 'stateDiff'",
+                    address
+                )));
+            }
+            (Some(state), None) => ExecutorStorage::Replace(slots(state)),
+            (None, Some(diff)) => ExecutorStorage::Patch(slots(diff)),
+            (None, None) => ExecutorStorage::Keep,
+        };
+        Ok(ExecutorAccountOverride {
+            address: address.as_bytes().to_vec(),
+            balance: self.balance.map(be_bytes),
+            nonce: self.nonce.map(|nonce| be_bytes(nonce.as_u64().into())),
+            code: self.code.clone().map(Into::into),
+            storage,
+        })
+    }
+}
+
+impl StateOverride {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The overrides for the executor, in the order of the addresses.
+    pub fn to_executor(&self) -> Result<Vec<ExecutorAccountOverride>, Error> {
+        self.0
+            .iter()
+            .map(|(address, account)| account.to_executor(address))
+            .collect()
+    }
+}
+
+impl BlockOverrides {
+    /// The overrides for the executor.
+    ///
+    /// CITA has no difficulty, randomness or base fee, so these are rejected
+    /// unless the base fee is zero.
+    pub fn to_executor(&self) -> Result<ExecutorBlockOverrides, Error> {
+        if self.difficulty.is_some() {
+            return Err(Error::invalid_params("difficulty can not be overridden"));
+        }
+        if self.random.is_some() {
+            return Err(Error::invalid_params("random can not be overridden"));
+        }
+        if self.base_fee.is_some_and(|fee| !fee.is_zero()) {
+            return Err(Error::invalid_params("base fee can not be overridden"));
+        }
+        let height = match self.number {
+            Some(number) if number > U256::from(u64::MAX) => {
+                return Err(Error::invalid_params("block number is too large"));
+            }
+            number => number.map(|number| number.as_u64()),
+        };
+        let timestamp = match self.time {
+            Some(time) => Some(
+                time.as_u64()
+                    .checked_mul(1000)
+                    .ok_or_else(|| Error::invalid_params("time is too large"))?,
+            ),
+            None => None,
+        };
+        Ok(ExecutorBlockOverrides {
+            height,
+            timestamp,
+            quota_limit: self.gas_limit.map(|limit| limit.as_u64()),
+            proposer: self.coinbase.map(|coinbase| coinbase.as_bytes().to_vec()),
+        })
+    }
+}
+
+impl OpenRpcSchema for AccountOverride {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("AccountOverride", |components| {
+            object_schema(
+                "AccountOverride",
+                vec![
+                    ("balance", components.schema::<Quantity>()),
+                    ("nonce", components.schema::<Quantity>()),
+                    ("code", components.schema::<Data>()),
+                    ("state", components.schema::<BTreeMap<String, Data32>>()),
+                    ("stateDiff", components.schema::<BTreeMap<String, Data32>>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for StateOverride {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("StateOverride", |components| {
+            json!({
+                "title": "StateOverride",
+                "type": "object",
+                "additionalProperties": components.schema::<AccountOverride>(),
+            })
+        })
+    }
+}
+
+impl OpenRpcSchema for BlockOverrides {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockOverrides", |components| {
+            object_schema(
+                "BlockOverrides",
+                vec![
+                    ("number", components.schema::<Quantity>()),
+                    ("difficulty", components.schema::<Quantity>()),
+                    ("time", components.schema::<Quantity>()),
+                    ("gasLimit", components.schema::<Quantity>()),
+                    ("coinbase", components.schema::<Data20>()),
+                    ("random", components.schema::<Data32>()),
+                    ("baseFee", components.schema::<Quantity>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_override() {
+        let overrides: StateOverride = serde_json::from_value(json!({
+            "0x000000000000000000000000000000000000000a": {
+                "balance": "0xde0b6b3a7640000",
+                "nonce": "0x2",
+                "code": "0x6000",
+                "stateDiff": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001":
+                        "0x00000000000000000000000000000000000000000000000000000000000000ff"
+                }
+            },
+            "0x000000000000000000000000000000000000000b": {}
+        }))
+        .unwrap();
+        let accounts = overrides.to_executor().unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].address, Address::from_low_u64_be(10).as_bytes());
+        assert_eq!(
+            accounts[0].balance,
+            Some(be_bytes(U256::from(1_000_000_000_000_000_000u64)))
+        );
+        assert_eq!(accounts[0].nonce, Some(be_bytes(U256::from(2))));
+        assert_eq!(accounts[0].code, Some(vec![0x60, 0x00]));
+        assert_eq!(
+            accounts[0].storage,
+            ExecutorStorage::Patch(vec![(
+                H256::from_low_u64_be(1).as_bytes().to_vec(),
+                H256::from_low_u64_be(0xff).as_bytes().to_vec()
+            )])
+        );
+        assert_eq!(accounts[1].balance, None);
+        assert_eq!(accounts[1].storage, ExecutorStorage::Keep);
+
+        let mut account = AccountOverride {
+            state: Some(BTreeMap::new()),
+            ..Default::default()
+        };
+        assert_eq!(
+            account.to_executor(&Address::zero()).unwrap().storage,
+            ExecutorStorage::Replace(vec![])
+        );
+        account.state_diff = Some(BTreeMap::new());
+        assert!(account.to_executor(&Address::zero()).is_err());
+    }
+
+    #[test]
+    fn test_block_overrides() {
+        let overrides: BlockOverrides = serde_json::from_value(json!({
+            "number": "0x10",
+            "time": "0x64",
+            "gasLimit": "0x1c9c380",
+            "coinbase": "0x000000000000000000000000000000000000000a",
+            "baseFee": "0x0"
+        }))
+        .unwrap();
+        assert_eq!(
+            overrides.to_executor().unwrap(),
+            ExecutorBlockOverrides {
+                height: Some(16),
+                timestamp: Some(100_000),
+                quota_limit: Some(30_000_000),
+                proposer: Some(Address::from_low_u64_be(10).as_bytes().to_vec()),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(BlockOverrides::default()).unwrap(),
+            json!({})
+        );
+
+        let overrides = BlockOverrides {
+            random: Some(H256::zero()),
+            ..Default::default()
+        };
+        assert!(overrides.to_executor().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::rpc_request::{hex_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::ethereum_types::{EthBlockHeader, EthLog};
+use crate::rpc_types::{Data32, Quantity, Version};
+use serde::de::{Deserialize, Deserializer, Error as DeError};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+
+/// The method name of the subscription notifications.
+pub const SUBSCRIPTION_METHOD: &str = "eth_subscription";
+
+/// Kind of `eth_subscribe`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// New block headers.
+    NewHeads,
+    /// Logs matching the filter.
+    Logs,
+    /// Hashes of the transactions entering the pool.
+    NewPendingTransactions,
+    /// Changes of the syncing status.
+    Syncing,
+}
+
+/// Subscription id, a random 16 bytes hex string.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SubscriptionId(String);
+
+impl SubscriptionId {
+    pub fn new(id: String) -> Self {
+        SubscriptionId(id)
+    }
+
+    pub fn random() -> Self {
+        SubscriptionId(format!("0x{:032x}", rand::random::<u128>()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SubscriptionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The progress of the syncing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub starting_block: Quantity,
+    pub current_block: Quantity,
+    pub highest_block: Quantity,
+}
+
+/// The result of a syncing notification, `false` when the syncing stopped, or
+/// `{"syncing": true, "status": {...}}` while syncing, as geth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncingResult {
+    NotSyncing,
+    Syncing(SyncStatus),
+}
+
+impl Serialize for SyncingResult {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            SyncingResult::NotSyncing => serializer.serialize_bool(false),
+            SyncingResult::Syncing(ref status) => {
+                let mut state = serializer.serialize_struct("SyncingResult", 2)?;
+                state.serialize_field("syncing", &true)?;
+                state.serialize_field("status", status)?;
+                state.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncingResult {
+    fn deserialize<D>(deserializer: D) -> Result<SyncingResult, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawSyncingResult {
+            Flag(bool),
+            Status { syncing: bool, status: SyncStatus },
+        }
+
+        match RawSyncingResult::deserialize(deserializer)? {
+            RawSyncingResult::Flag(false) | RawSyncingResult::Status { syncing: false, .. } => {
+                Ok(SyncingResult::NotSyncing)
+            }
+            RawSyncingResult::Status { status, .. } => Ok(SyncingResult::Syncing(status)),
+            RawSyncingResult::Flag(true) => Err(D::Error::custom("the syncing status is missing")),
+        }
+    }
+}
+
+/// The result carried by a subscription notification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum SubscriptionResult {
+    Header(Box<EthBlockHeader>),
+    Log(Box<EthLog>),
+    TransactionHash(Data32),
+    Syncing(SyncingResult),
+}
+
+/// The `params` of a subscription notification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscriptionParams {
+    pub subscription: SubscriptionId,
+    pub result: SubscriptionResult,
+}
+
+/// `eth_subscription` notification pushed to the subscribers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscriptionNotification {
+    pub jsonrpc: Version,
+    pub method: String,
+    pub params: SubscriptionParams,
+}
+
+impl SubscriptionNotification {
+    pub fn new(subscription: SubscriptionId, result: SubscriptionResult) -> Self {
+        SubscriptionNotification {
+            jsonrpc: Version::V2,
+            method: SUBSCRIPTION_METHOD.to_owned(),
+            params: SubscriptionParams {
+                subscription,
+                result,
+            },
+        }
+    }
+}
+
+impl OpenRpcSchema for SubscriptionKind {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("SubscriptionKind", |_| {
+            json!({
+                "title": "SubscriptionKind",
+                "type": "string",
+                "enum": ["newHeads", "logs", "newPendingTransactions", "syncing"],
+            })
+        })
+    }
+}
+
+impl OpenRpcSchema for SubscriptionId {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("SubscriptionId", |_| {
+            hex_schema("SubscriptionId", "^0x[0-9a-fA-F]{32}$")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_types::H256;
+
+    #[test]
+    fn test_subscription_kind_serde() {
+        let testdata = vec![
+            (SubscriptionKind::NewHeads, r#""newHeads""#),
+            (SubscriptionKind::Logs, r#""logs""#),
+            (
+                SubscriptionKind::NewPendingTransactions,
+                r#""newPendingTransactions""#,
+            ),
+            (SubscriptionKind::Syncing, r#""syncing""#),
+        ];
+        for (kind, expected) in testdata.into_iter() {
+            assert_eq!(serde_json::to_string(&kind).unwrap(), expected);
+            assert_eq!(
+                serde_json::from_str::<SubscriptionKind>(expected).unwrap(),
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn test_subscription_notification_serde() {
+        let id = SubscriptionId::random();
+        assert_eq!(id.as_str().len(), 34);
+        assert!(id.as_str().starts_with("0x"));
+
+        let notification = SubscriptionNotification::new(
+            SubscriptionId::new("0x9ce59a13059e417087c02d3236a0b1cc".to_owned()),
+            SubscriptionResult::TransactionHash(Data32::new(H256::from_low_u64_be(1))),
+        );
+        let value = json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": {
+                "subscription": "0x9ce59a13059e417087c02d3236a0b1cc",
+                "result": "0x0000000000000000000000000000000000000000000000000000000000000001"
+            }
+        });
+        assert_eq!(serde_json::to_value(&notification).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<SubscriptionNotification>(value).unwrap(),
+            notification
+        );
+    }
+
+    #[test]
+    fn test_syncing_result_serde() {
+        let syncing = SyncingResult::Syncing(SyncStatus {
+            starting_block: Quantity::from(0x384u64),
+            current_block: Quantity::from(0x386u64),
+            highest_block: Quantity::from(0x454u64),
+        });
+        let value = json!({
+            "syncing": true,
+            "status": {
+                "startingBlock": "0x384",
+                "currentBlock": "0x386",
+                "highestBlock": "0x454"
+            }
+        });
+        assert_eq!(serde_json::to_value(&syncing).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<SubscriptionResult>(value).unwrap(),
+            SubscriptionResult::Syncing(syncing)
+        );
+
+        let stopped = SubscriptionResult::Syncing(SyncingResult::NotSyncing);
+        assert_eq!(serde_json::to_value(&stopped).unwrap(), json!(false));
+        assert_eq!(
+            serde_json::from_value::<SubscriptionResult>(json!(false)).unwrap(),
+            stopped
+        );
+        assert!(serde_json::from_value::<SyncingResult>(json!(true)).is_err());
+    }
+}
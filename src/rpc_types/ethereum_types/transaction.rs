@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::ethereum_types::EthTransactionRequest;
 use crate::rpc_types::parity_types::Action;
 use crate::rpc_types::{parity_types, BlockTransaction, Data, Quantity, RpcTransaction};
+use crate::rpc_types::{Data20, Data32};
 use cita_cloud_proto::blockchain::{raw_transaction, RawTransaction, Transaction};
 use cita_tool::{pubkey_to_address, Signature, UnverifiedTransaction};
 use ethereum_types::{Address, H256, U256, U64};
 use protobuf::parse_from_bytes;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
 use web3::signing::recover;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
@@ -28,6 +32,47 @@ pub struct AccessList {
     pub storage_keys: Vec<H256>,
 }
 
+/// Result of `eth_createAccessList`, the error is of the call with the access
+/// list.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListWithGasUsed {
+    pub access_list: Vec<AccessList>,
+    pub gas_used: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// The precompiled contracts up to Berlin, 0x01 to 0x09.
+const PRECOMPILES: u64 = 9;
+
+impl AccessList {
+    /// The access list of the addresses and the storage slots touched by a
+    /// call, as geth.
+    ///
+    /// The sender, the recipient and the precompiles are warm already, so they
+    /// are excluded unless their storage is touched.
+    pub fn from_touched(
+        touched: &BTreeMap<Address, BTreeSet<H256>>,
+        from: &Address,
+        to: Option<&Address>,
+    ) -> Vec<AccessList> {
+        let excluded = |address: &Address| {
+            address == from
+                || Some(address) == to
+                || (1..=PRECOMPILES).any(|n| *address == Address::from_low_u64_be(n))
+        };
+        touched
+            .iter()
+            .filter(|(address, slots)| !slots.is_empty() || !excluded(address))
+            .map(|(address, slots)| AccessList {
+                address: *address,
+                storage_keys: slots.iter().cloned().collect(),
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct EthRpcTransaction {
@@ -205,32 +250,38 @@ impl From<BlockTransaction> for EthBlockTransaction {
     }
 }
 
-impl From<RawTransaction> for EthBlockTransaction {
-    fn from(origin: RawTransaction) -> Self {
-        match origin.tx.unwrap() {
+// The `v`, `r` and `s` of the signature.
+fn signature_vrs(signature: &[u8]) -> Result<(U64, U256, U256), String> {
+    match Signature::from(signature) {
+        Signature::Secp256k1(sig) => Ok((
+            U64::from(sig.v()),
+            U256::from_big_endian(&sig.r()),
+            U256::from_big_endian(&sig.s()),
+        )),
+        Signature::Sm2(sig) => Ok((
+            U64::from(2), // no use recovery id, input a wrong number
+            U256::from_big_endian(&sig.r()),
+            U256::from_big_endian(&sig.s()),
+        )),
+        Signature::Null => Err("null signature".to_owned()),
+    }
+}
+
+impl EthBlockTransaction {
+    /// Convert the raw transaction, which fails if the body, the witness or
+    /// the signature is missing.
+    pub fn try_from_raw(origin: RawTransaction) -> Result<Self, String> {
+        match origin.tx.ok_or("no transaction")? {
             raw_transaction::Tx::NormalTx(tx) => {
-                let orin_tx = tx.transaction.unwrap();
-                let witness = tx.witness.unwrap();
-                let sig = Signature::from(&witness.signature);
-                let (v, r, s) = match sig {
-                    Signature::Secp256k1(sig) => (
-                        U64::from(sig.v()),
-                        U256::from_big_endian(&sig.r()),
-                        U256::from_big_endian(&sig.s()),
-                    ),
-                    Signature::Sm2(sig) => (
-                        U64::from(2), // no use recovery id, input a wrong number
-                        U256::from_big_endian(&sig.r()),
-                        U256::from_big_endian(&sig.s()),
-                    ),
-                    Signature::Null => panic!("null signature"),
-                };
+                let orin_tx = tx.transaction.ok_or("no transaction body")?;
+                let witness = tx.witness.ok_or("no witness")?;
+                let (v, r, s) = signature_vrs(&witness.signature)?;
                 let to = if orin_tx.to.len() == 20 {
                     Some(Address::from_slice(orin_tx.to.as_slice()))
                 } else {
                     None
                 };
-                EthBlockTransaction::Full(EthRpcTransaction {
+                Ok(EthBlockTransaction::Full(EthRpcTransaction {
                     block_hash: Default::default(),
                     block_number: Default::default(),
                     from: Address::from_slice(&witness.sender),
@@ -251,26 +302,13 @@ impl From<RawTransaction> for EthBlockTransaction {
                     r,
                     s,
                     raw: None,
-                })
+                }))
             }
             raw_transaction::Tx::UtxoTx(utxo) => {
-                let utxo_tx = utxo.transaction.unwrap();
-                let witness = utxo.witnesses[0].clone();
-                let sig = Signature::from(&witness.signature);
-                let (v, r, s) = match sig {
-                    Signature::Secp256k1(sig) => (
-                        U64::from(sig.v()),
-                        U256::from_big_endian(&sig.r()),
-                        U256::from_big_endian(&sig.s()),
-                    ),
-                    Signature::Sm2(sig) => (
-                        U64::from(2), // no use recovery id, input a wrong number
-                        U256::from_big_endian(&sig.r()),
-                        U256::from_big_endian(&sig.s()),
-                    ),
-                    Signature::Null => panic!("null signature"),
-                };
-                EthBlockTransaction::Full(EthRpcTransaction {
+                let utxo_tx = utxo.transaction.ok_or("no transaction body")?;
+                let witness = utxo.witnesses.first().ok_or("no witness")?;
+                let (v, r, s) = signature_vrs(&witness.signature)?;
+                Ok(EthBlockTransaction::Full(EthRpcTransaction {
                     block_hash: H256::from_slice(utxo_tx.pre_tx_hash.as_slice()),
                     block_number: U256::from(utxo_tx.lock_id),
                     from: Address::from_slice(&witness.sender),
@@ -291,8 +329,135 @@ impl From<RawTransaction> for EthBlockTransaction {
                     r,
                     s,
                     raw: None,
-                })
+                }))
             }
         }
     }
 }
+
+/// The hash of the raw transaction, zero if there is no transaction.
+pub fn raw_transaction_hash(origin: &RawTransaction) -> H256 {
+    match origin.tx {
+        Some(raw_transaction::Tx::NormalTx(ref tx)) => H256::from_slice(&tx.transaction_hash),
+        Some(raw_transaction::Tx::UtxoTx(ref utxo)) => H256::from_slice(&utxo.transaction_hash),
+        None => H256::zero(),
+    }
+}
+
+impl From<RawTransaction> for EthBlockTransaction {
+    fn from(origin: RawTransaction) -> Self {
+        EthBlockTransaction::try_from_raw(origin).unwrap()
+    }
+}
+
+impl OpenRpcSchema for AccessList {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("AccessList", |components| {
+            object_schema(
+                "AccessList",
+                vec![
+                    ("address", components.schema::<Data20>()),
+                    ("storageKeys", components.schema::<Vec<Data32>>()),
+                ],
+                &["address", "storageKeys"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for AccessListWithGasUsed {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("AccessListWithGasUsed", |components| {
+            object_schema(
+                "AccessListWithGasUsed",
+                vec![
+                    ("accessList", components.schema::<Vec<AccessList>>()),
+                    ("gasUsed", components.schema::<Quantity>()),
+                    ("error", components.schema::<String>()),
+                ],
+                &["accessList", "gasUsed"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for EthRpcTransaction {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("EthRpcTransaction", |components| {
+            object_schema(
+                "EthRpcTransaction",
+                vec![
+                    ("blockHash", components.schema::<Data32>()),
+                    ("blockNumber", components.schema::<Quantity>()),
+                    ("from", components.schema::<Data20>()),
+                    ("gas", components.schema::<Quantity>()),
+                    ("gasPrice", components.schema::<Quantity>()),
+                    ("maxFeePerGas", components.schema::<Quantity>()),
+                    ("maxPriorityFeePerGas", components.schema::<Quantity>()),
+                    ("hash", components.schema::<Data32>()),
+                    ("input", components.schema::<Data>()),
+                    ("nonce", components.schema::<Quantity>()),
+                    ("to", components.schema::<Option<Data20>>()),
+                    ("transactionIndex", components.schema::<Quantity>()),
+                    ("value", components.schema::<Quantity>()),
+                    ("type", components.schema::<Quantity>()),
+                    ("accessList", components.schema::<Vec<AccessList>>()),
+                    ("chainId", components.schema::<Quantity>()),
+                    ("v", components.schema::<Quantity>()),
+                    ("r", components.schema::<Quantity>()),
+                    ("s", components.schema::<Quantity>()),
+                    ("raw", components.schema::<Data>()),
+                ],
+                &["hash", "from", "input", "value", "gas"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for EthBlockTransaction {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        json!({
+            "oneOf": [components.schema::<Data32>(), components.schema::<EthRpcTransaction>()],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_list_from_touched() {
+        let from = Address::from_low_u64_be(0xa);
+        let to = Address::from_low_u64_be(0xb);
+        let other = Address::from_low_u64_be(0xc);
+        let slot = H256::from_low_u64_be(1);
+        let touched: BTreeMap<Address, BTreeSet<H256>> = vec![
+            (from, BTreeSet::new()),
+            (to, vec![slot].into_iter().collect()),
+            (other, BTreeSet::new()),
+            (Address::from_low_u64_be(1), BTreeSet::new()),
+        ]
+        .into_iter()
+        .collect();
+
+        let result = AccessListWithGasUsed {
+            access_list: AccessList::from_touched(&touched, &from, Some(&to)),
+            gas_used: U256::from(30000),
+            error: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "accessList": [{
+                    "address": "0x000000000000000000000000000000000000000b",
+                    "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+                }, {
+                    "address": "0x000000000000000000000000000000000000000c",
+                    "storageKeys": []
+                }],
+                "gasUsed": "0x7530"
+            })
+        );
+    }
+}
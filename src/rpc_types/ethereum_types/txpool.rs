@@ -0,0 +1,303 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Results of `txpool_content`, `txpool_inspect` and `txpool_status`.
+//!
+//! CITA has no queue of the transactions waiting for the nonce, all the
+//! transactions in the pool are pending.
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::ethereum_types::{EthBlockTransaction, EthRpcTransaction};
+use crate::rpc_types::PoolTxNum;
+use crate::rpc_types::Quantity;
+use cita_cloud_proto::blockchain::{raw_transaction, RawTransactions};
+use ethereum_types::{Address, U256, U64};
+use keccak_hash::keccak;
+use rustc_serialize::hex::ToHex;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Transactions grouped by the sender and the nonce.
+pub type TxpoolGroup<T> = BTreeMap<Address, BTreeMap<String, T>>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Default)]
+pub struct TxpoolStatus {
+    pub pending: U64,
+    pub queued: U64,
+}
+
+impl From<PoolTxNum> for TxpoolStatus {
+    fn from(origin: PoolTxNum) -> Self {
+        TxpoolStatus {
+            pending: U64::from(origin.num),
+            queued: U64::zero(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
+pub struct TxpoolContent {
+    pub pending: TxpoolGroup<EthRpcTransaction>,
+    pub queued: TxpoolGroup<EthRpcTransaction>,
+}
+
+/// The summaries of `TxpoolContent`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Default)]
+pub struct TxpoolInspect {
+    pub pending: TxpoolGroup<String>,
+    pub queued: TxpoolGroup<String>,
+}
+
+// The nonce of CITA is a string, the decimal or hexadecimal one is read as
+// the number.
+fn parse_nonce(nonce: &str) -> Option<U256> {
+    if nonce.starts_with("0x") || nonce.starts_with("0X") {
+        U256::from_str_radix(&nonce[2..], 16).ok()
+    } else {
+        U256::from_dec_str(nonce).ok()
+    }
+}
+
+impl From<RawTransactions> for TxpoolContent {
+    /// The transactions of the pool keyed by the decimal nonce as geth, the
+    /// nonce which is not a number is kept as it is. The UTXO transactions
+    /// have no sender nor nonce, they are skipped, as are the ones can't be
+    /// read.
+    fn from(origin: RawTransactions) -> Self {
+        let mut content = TxpoolContent::default();
+        for raw in origin.body {
+            let nonce = match raw.tx {
+                Some(raw_transaction::Tx::NormalTx(ref tx)) => tx
+                    .transaction
+                    .as_ref()
+                    .map(|tx| tx.nonce.clone())
+                    .unwrap_or_default(),
+                _ => continue,
+            };
+            if let Ok(EthBlockTransaction::Full(mut tx)) = EthBlockTransaction::try_from_raw(raw) {
+                let key = match parse_nonce(&nonce) {
+                    Some(number) => {
+                        tx.nonce = number;
+                        number.to_string()
+                    }
+                    None => nonce,
+                };
+                content.pending.entry(tx.from).or_default().insert(key, tx);
+            }
+        }
+        content
+    }
+}
+
+/// The address with the checksum of EIP-55.
+fn checksum_address(address: &Address) -> String {
+    let hex = address.as_bytes().to_hex();
+    let hash = keccak(hex.as_bytes());
+    let checksummed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let byte = hash[i / 2];
+            let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+impl TxpoolInspect {
+    /// The summary as geth, `to: value wei + gas gas × gasPrice wei`.
+    pub fn summary(tx: &EthRpcTransaction) -> String {
+        let to = match tx.to {
+            Some(ref to) => checksum_address(to),
+            None => "contract creation".to_owned(),
+        };
+        format!(
+            "{}: {} wei + {} gas × {} wei",
+            to, tx.value, tx.gas, tx.gas_price
+        )
+    }
+}
+
+impl From<&TxpoolContent> for TxpoolInspect {
+    fn from(origin: &TxpoolContent) -> Self {
+        let summarize = |group: &TxpoolGroup<EthRpcTransaction>| {
+            group
+                .iter()
+                .map(|(sender, txs)| {
+                    let summaries = txs
+                        .iter()
+                        .map(|(nonce, tx)| (nonce.clone(), TxpoolInspect::summary(tx)))
+                        .collect();
+                    (*sender, summaries)
+                })
+                .collect()
+        };
+        TxpoolInspect {
+            pending: summarize(&origin.pending),
+            queued: summarize(&origin.queued),
+        }
+    }
+}
+
+impl OpenRpcSchema for TxpoolStatus {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TxpoolStatus", |components| {
+            object_schema(
+                "TxpoolStatus",
+                vec![
+                    ("pending", components.schema::<Quantity>()),
+                    ("queued", components.schema::<Quantity>()),
+                ],
+                &["pending", "queued"],
+            )
+        })
+    }
+}
+
+// Grouped by the sender, and then by the nonce.
+fn group_schema<T: OpenRpcSchema>(components: &mut OpenRpcComponents) -> Value {
+    components.schema::<BTreeMap<String, BTreeMap<String, T>>>()
+}
+
+impl OpenRpcSchema for TxpoolContent {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TxpoolContent", |components| {
+            object_schema(
+                "TxpoolContent",
+                vec![
+                    ("pending", group_schema::<EthRpcTransaction>(components)),
+                    ("queued", group_schema::<EthRpcTransaction>(components)),
+                ],
+                &["pending", "queued"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for TxpoolInspect {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TxpoolInspect", |components| {
+            object_schema(
+                "TxpoolInspect",
+                vec![
+                    ("pending", group_schema::<String>(components)),
+                    ("queued", group_schema::<String>(components)),
+                ],
+                &["pending", "queued"],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cita_cloud_proto::blockchain::{
+        RawTransaction, Transaction, UnverifiedTransaction, UnverifiedUtxoTransaction, Witness,
+    };
+    use ethereum_types::{H256, U256};
+
+    fn value(n: u64) -> Vec<u8> {
+        let mut value = vec![0; 32];
+        U256::from(n).to_big_endian(&mut value);
+        value
+    }
+
+    fn transaction(sender: u64, nonce: &str, to: Vec<u8>) -> RawTransaction {
+        RawTransaction {
+            tx: Some(raw_transaction::Tx::NormalTx(UnverifiedTransaction {
+                transaction: Some(Transaction {
+                    to,
+                    nonce: nonce.to_owned(),
+                    quota: 21000,
+                    value: value(10),
+                    ..Default::default()
+                }),
+                transaction_hash: H256::from_low_u64_be(sender).as_bytes().to_vec(),
+                witness: Some(Witness {
+                    signature: vec![0; 65],
+                    sender: Address::from_low_u64_be(sender).as_bytes().to_vec(),
+                }),
+            })),
+        }
+    }
+
+    #[test]
+    fn test_txpool_content_and_inspect() {
+        let to = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+            .parse::<Address>()
+            .unwrap();
+        let mut unsigned = transaction(4, "10", vec![]);
+        if let Some(raw_transaction::Tx::NormalTx(ref mut tx)) = unsigned.tx {
+            tx.witness.as_mut().unwrap().signature = vec![];
+        }
+        let pool = RawTransactions {
+            body: vec![
+                transaction(1, "10", to.as_bytes().to_vec()),
+                transaction(1, "0x1", vec![]),
+                transaction(2, "10", to.as_bytes().to_vec()),
+                transaction(2, "nonce", to.as_bytes().to_vec()),
+                RawTransaction { tx: None },
+                unsigned,
+                RawTransaction {
+                    tx: Some(raw_transaction::Tx::UtxoTx(UnverifiedUtxoTransaction {
+                        transaction_hash: H256::from_low_u64_be(3).as_bytes().to_vec(),
+                        ..Default::default()
+                    })),
+                },
+            ],
+        };
+        let content = TxpoolContent::from(pool);
+        assert!(content.queued.is_empty());
+        assert_eq!(content.pending.len(), 2);
+        let sender = Address::from_low_u64_be(1);
+        assert_eq!(content.pending[&sender].len(), 2);
+        assert_eq!(content.pending[&sender]["10"].to, Some(to));
+        assert_eq!(content.pending[&sender]["1"].nonce, U256::one());
+        let sender2 = Address::from_low_u64_be(2);
+        assert_eq!(content.pending[&sender2]["10"].nonce, U256::from(10));
+        assert_eq!(content.pending[&sender2]["nonce"].nonce, U256::zero());
+
+        let inspect = TxpoolInspect::from(&content);
+        assert_eq!(
+            inspect.pending[&sender]["10"],
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed: 10 wei + 21000 gas × 0 wei"
+        );
+        assert_eq!(
+            inspect.pending[&sender]["1"],
+            "contract creation: 10 wei + 21000 gas × 0 wei"
+        );
+
+        let json = serde_json::to_value(&inspect).unwrap();
+        assert_eq!(
+            json["pending"]["0x0000000000000000000000000000000000000001"]["10"],
+            json!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed: 10 wei + 21000 gas × 0 wei")
+        );
+        assert_eq!(json["queued"], json!({}));
+    }
+
+    #[test]
+    fn test_txpool_status() {
+        let status = TxpoolStatus::from(PoolTxNum { num: 16 });
+        assert_eq!(
+            serde_json::to_value(status).unwrap(),
+            json!({"pending": "0x10", "queued": "0x0"})
+        );
+    }
+}
@@ -0,0 +1,634 @@
+// Copyright Rivtower Technologies LLC.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed structured data of EIP-712, as the `eth_signTypedData_v4` of
+//! MetaMask.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethereum_types::{Address, H256, U256};
+use keccak_hash::keccak;
+use rustc_serialize::hex::FromHex;
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::{Data20, Data32, Quantity};
+use crate::Error;
+
+/// The name of the domain type.
+pub const EIP712_DOMAIN: &str = "EIP712Domain";
+
+/// A member of a struct type.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Eip712Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+impl Eip712Field {
+    pub fn new(name: &str, type_: &str) -> Self {
+        Eip712Field {
+            name: name.to_owned(),
+            type_: type_.to_owned(),
+        }
+    }
+}
+
+/// The struct types by name.
+pub type Eip712Types = BTreeMap<String, Vec<Eip712Field>>;
+
+/// The domain, all fields of it are optional.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_chain_id"
+    )]
+    pub chain_id: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<H256>,
+}
+
+// The chain id is a number or a string in the wild.
+fn deserialize_chain_id<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .map(|value| parse_uint(&value))
+        .transpose()
+        .map_err(|err| de::Error::custom(err.message))
+}
+
+impl Eip712Domain {
+    /// The members of the domain type with the fields present, in the order of
+    /// EIP-712.
+    pub fn fields(&self) -> Vec<Eip712Field> {
+        let mut fields = Vec::new();
+        if self.name.is_some() {
+            fields.push(Eip712Field::new("name", "string"));
+        }
+        if self.version.is_some() {
+            fields.push(Eip712Field::new("version", "string"));
+        }
+        if self.chain_id.is_some() {
+            fields.push(Eip712Field::new("chainId", "uint256"));
+        }
+        if self.verifying_contract.is_some() {
+            fields.push(Eip712Field::new("verifyingContract", "address"));
+        }
+        if self.salt.is_some() {
+            fields.push(Eip712Field::new("salt", "bytes32"));
+        }
+        fields
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypedData {
+    types: Eip712Types,
+    primary_type: String,
+    domain: Eip712Domain,
+    message: Value,
+}
+
+/// Typed structured data to sign.
+///
+/// It is deserialized from an object or a string of the object, both are
+/// accepted by the wallets.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712TypedData {
+    pub types: Eip712Types,
+    pub primary_type: String,
+    pub domain: Eip712Domain,
+    pub message: Value,
+}
+
+impl<'de> Deserialize<'de> for Eip712TypedData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = match Value::deserialize(deserializer)? {
+            Value::String(json) => serde_json::from_str::<TypedData>(&json),
+            value => serde_json::from_value::<TypedData>(value),
+        }
+        .map_err(de::Error::custom)?;
+        Ok(Eip712TypedData {
+            types: data.types,
+            primary_type: data.primary_type,
+            domain: data.domain,
+            message: data.message,
+        })
+    }
+}
+
+fn invalid<M: Into<String>>(message: M) -> Error {
+    Error::invalid_params(message)
+}
+
+// The element type and the length of an array type.
+fn split_array(type_: &str) -> Option<(&str, Option<usize>)> {
+    let type_ = type_.strip_suffix(']')?;
+    let start = type_.rfind('[')?;
+    let len = &type_[start + 1..];
+    Some((&type_[..start], len.parse().ok()))
+}
+
+fn base_type(type_: &str) -> &str {
+    type_.find('[').map_or(type_, |start| &type_[..start])
+}
+
+// The bits of `uint<M>` and `int<M>`, where the empty `M` is 256.
+fn parse_bits(bits: &str, type_: &str) -> Result<usize, Error> {
+    let bits = if bits.is_empty() {
+        256
+    } else {
+        bits.parse::<usize>()
+            .map_err(|_| invalid(format!("unknown type {}", type_)))?
+    };
+    if bits == 0 || bits > 256 || bits % 8 != 0 {
+        return Err(invalid(format!("unknown type {}", type_)));
+    }
+    Ok(bits)
+}
+
+fn parse_uint(value: &Value) -> Result<U256, Error> {
+    let parsed = match value {
+        Value::Number(number) => number.as_u64().map(U256::from),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).ok(),
+            None => U256::from_dec_str(s).ok(),
+        },
+        _ => None,
+    };
+    parsed.ok_or_else(|| invalid(format!("invalid integer {}", value)))
+}
+
+// The sign and the magnitude of the integer.
+fn parse_int(value: &Value) -> Result<(bool, U256), Error> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .map(|n| (n < 0, U256::from(n.unsigned_abs())))
+            .or_else(|| number.as_u64().map(|n| (false, U256::from(n))))
+            .ok_or_else(|| invalid(format!("invalid integer {}", value))),
+        Value::String(s) => match s.strip_prefix('-') {
+            Some(magnitude) => Ok((true, parse_uint(&Value::String(magnitude.to_owned()))?)),
+            None => Ok((false, parse_uint(value)?)),
+        },
+        _ => Err(invalid(format!("invalid integer {}", value))),
+    }
+}
+
+fn parse_bytes(value: &Value) -> Result<Vec<u8>, Error> {
+    value
+        .as_str()
+        .and_then(|s| s.strip_prefix("0x"))
+        .and_then(|hex| hex.from_hex().ok())
+        .ok_or_else(|| invalid(format!("invalid bytes {}", value)))
+}
+
+fn uint_word(value: U256) -> H256 {
+    let mut word = H256::zero();
+    value.to_big_endian(word.as_bytes_mut());
+    word
+}
+
+impl Eip712TypedData {
+    fn fields(&self, type_: &str) -> Result<&[Eip712Field], Error> {
+        self.types
+            .get(type_)
+            .map(Vec::as_slice)
+            .ok_or_else(|| invalid(format!("unknown type {}", type_)))
+    }
+
+    fn collect_dependencies<'a>(&'a self, type_: &'a str, found: &mut BTreeSet<&'a str>) {
+        if !found.insert(type_) {
+            return;
+        }
+        for field in self.types.get(type_).into_iter().flatten() {
+            let base = base_type(&field.type_);
+            if self.types.contains_key(base) {
+                self.collect_dependencies(base, found);
+            }
+        }
+    }
+
+    /// `encodeType`, the type itself followed by the struct types referenced
+    /// by it, sorted by name.
+    pub fn encode_type(&self, type_: &str) -> Result<String, Error> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(type_, &mut dependencies);
+        dependencies.remove(type_);
+        let mut encoded = String::new();
+        for name in std::iter::once(type_).chain(dependencies) {
+            let members: Vec<String> = self
+                .fields(name)?
+                .iter()
+                .map(|field| format!("{} {}", field.type_, field.name))
+                .collect();
+            encoded.push_str(&format!("{}({})", name, members.join(",")));
+        }
+        Ok(encoded)
+    }
+
+    pub fn type_hash(&self, type_: &str) -> Result<H256, Error> {
+        Ok(keccak(self.encode_type(type_)?))
+    }
+
+    /// `encodeData`, the type hash followed by the encoded members.
+    pub fn encode_data(&self, type_: &str, data: &Value) -> Result<Vec<u8>, Error> {
+        let data = data
+            .as_object()
+            .ok_or_else(|| invalid(format!("{} should be an object", type_)))?;
+        let mut encoded = self.type_hash(type_)?.as_bytes().to_vec();
+        for field in self.fields(type_)? {
+            let value = data
+                .get(&field.name)
+                .ok_or_else(|| invalid(format!("{} of {} is missing", field.name, type_)))?;
+            encoded.extend_from_slice(self.encode_value(&field.type_, value)?.as_bytes());
+        }
+        Ok(encoded)
+    }
+
+    /// `hashStruct`, the hash of the encoded data.
+    pub fn hash_struct(&self, type_: &str, data: &Value) -> Result<H256, Error> {
+        Ok(keccak(self.encode_data(type_, data)?))
+    }
+
+    // The 32 bytes of a member value.
+    fn encode_value(&self, type_: &str, value: &Value) -> Result<H256, Error> {
+        if let Some((element, len)) = split_array(type_) {
+            let items = value
+                .as_array()
+                .ok_or_else(|| invalid(format!("{} should be an array", type_)))?;
+            if len.is_some_and(|len| len != items.len()) {
+                return Err(invalid(format!("invalid length of {}", type_)));
+            }
+            let mut encoded = Vec::with_capacity(items.len() * 32);
+            for item in items {
+                encoded.extend_from_slice(self.encode_value(element, item)?.as_bytes());
+            }
+            return Ok(keccak(encoded));
+        }
+        if self.types.contains_key(type_) {
+            return self.hash_struct(type_, value);
+        }
+
+        match type_ {
+            "string" => value
+                .as_str()
+                .map(keccak)
+                .ok_or_else(|| invalid(format!("invalid string {}", value))),
+            "bytes" => Ok(keccak(parse_bytes(value)?)),
+            "bool" => value
+                .as_bool()
+                .map(|b| H256::from_low_u64_be(b as u64))
+                .ok_or_else(|| invalid(format!("invalid bool {}", value))),
+            "address" => {
+                let bytes = parse_bytes(value)?;
+                if bytes.len() != 20 {
+                    return Err(invalid(format!("invalid address {}", value)));
+                }
+                Ok(H256::from(Address::from_slice(&bytes)))
+            }
+            _ => {
+                if let Some(len) = type_.strip_prefix("bytes") {
+                    let len = len
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|len| (1..=32).contains(len))
+                        .ok_or_else(|| invalid(format!("unknown type {}", type_)))?;
+                    let bytes = parse_bytes(value)?;
+                    if bytes.len() != len {
+                        return Err(invalid(format!("invalid {} {}", type_, value)));
+                    }
+                    let mut word = H256::zero();
+                    word.as_bytes_mut()[..len].copy_from_slice(&bytes);
+                    Ok(word)
+                } else if let Some(bits) = type_.strip_prefix("uint") {
+                    let bits = parse_bits(bits, type_)?;
+                    let value = parse_uint(value)?;
+                    if value.bits() > bits {
+                        return Err(invalid(format!("{} overflows {}", value, type_)));
+                    }
+                    Ok(uint_word(value))
+                } else if let Some(bits) = type_.strip_prefix("int") {
+                    let bits = parse_bits(bits, type_)?;
+                    let (negative, magnitude) = parse_int(value)?;
+                    let limit = U256::one() << (bits - 1);
+                    if magnitude > limit || (!negative && magnitude == limit) {
+                        return Err(invalid(format!("{} overflows {}", value, type_)));
+                    }
+                    if negative {
+                        // Two's complement in 256 bits.
+                        Ok(uint_word((!magnitude).overflowing_add(U256::one()).0))
+                    } else {
+                        Ok(uint_word(magnitude))
+                    }
+                } else {
+                    Err(invalid(format!("unknown type {}", type_)))
+                }
+            }
+        }
+    }
+
+    // The types with the domain type, which is derived from the domain if
+    // it is not given.
+    fn with_domain_type(&self) -> Cow<'_, Self> {
+        if self.types.contains_key(EIP712_DOMAIN) {
+            Cow::Borrowed(self)
+        } else {
+            let mut data = self.clone();
+            data.types
+                .insert(EIP712_DOMAIN.to_owned(), self.domain.fields());
+            Cow::Owned(data)
+        }
+    }
+
+    pub fn domain_separator(&self) -> Result<H256, Error> {
+        let domain = serde_json::to_value(&self.domain).unwrap();
+        self.with_domain_type().hash_struct(EIP712_DOMAIN, &domain)
+    }
+
+    /// The hash to sign, `keccak256("\x19\x01" ‖ domainSeparator ‖
+    /// hashStruct(message))`.
+    pub fn signing_hash(&self) -> Result<H256, Error> {
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(self.domain_separator()?.as_bytes());
+        // Only the domain is signed if it is the primary type.
+        if self.primary_type != EIP712_DOMAIN {
+            encoded.extend_from_slice(
+                self.hash_struct(&self.primary_type, &self.message)?
+                    .as_bytes(),
+            );
+        }
+        Ok(keccak(encoded))
+    }
+}
+
+impl OpenRpcSchema for Eip712Field {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Eip712Field", |components| {
+            object_schema(
+                "Eip712Field",
+                vec![
+                    ("name", components.schema::<String>()),
+                    ("type", components.schema::<String>()),
+                ],
+                &["name", "type"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for Eip712Domain {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Eip712Domain", |components| {
+            object_schema(
+                "Eip712Domain",
+                vec![
+                    ("name", components.schema::<String>()),
+                    ("version", components.schema::<String>()),
+                    ("chainId", components.schema::<Quantity>()),
+                    ("verifyingContract", components.schema::<Data20>()),
+                    ("salt", components.schema::<Data32>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for Eip712TypedData {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Eip712TypedData", |components| {
+            object_schema(
+                "Eip712TypedData",
+                vec![
+                    ("types", components.schema::<Eip712Types>()),
+                    ("primaryType", components.schema::<String>()),
+                    ("domain", components.schema::<Eip712Domain>()),
+                    ("message", json!({ "type": "object" })),
+                ],
+                &["types", "primaryType", "domain", "message"],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example of EIP-712.
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    // The example of `eth_signTypedData_v4` with arrays.
+    const MAIL_WITH_ARRAYS: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Group": [
+                {"name": "name", "type": "string"},
+                {"name": "members", "type": "Person[]"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person[]"},
+                {"name": "contents", "type": "string"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallets", "type": "address[]"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {
+                "name": "Cow",
+                "wallets": [
+                    "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+                    "0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"
+                ]
+            },
+            "to": [{
+                "name": "Bob",
+                "wallets": [
+                    "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+                    "0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+                    "0xB0B0b0b0b0b0B000000000000000000000000000"
+                ]
+            }],
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    fn h256(hex: &str) -> H256 {
+        hex.parse().unwrap()
+    }
+
+    #[test]
+    fn test_mail() {
+        let data: Eip712TypedData = serde_json::from_str(MAIL).unwrap();
+        assert_eq!(
+            data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            data.type_hash("Mail").unwrap(),
+            h256("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")
+        );
+        assert_eq!(
+            data.hash_struct("Mail", &data.message).unwrap(),
+            h256("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+        assert_eq!(
+            data.domain_separator().unwrap(),
+            h256("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+        assert_eq!(
+            data.signing_hash().unwrap(),
+            h256("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+
+        // The same without the domain type, and as a string.
+        let mut value: Value = serde_json::from_str(MAIL).unwrap();
+        value["types"]
+            .as_object_mut()
+            .unwrap()
+            .remove(EIP712_DOMAIN);
+        let json = serde_json::to_string(&value).unwrap();
+        let derived: Eip712TypedData = serde_json::from_value(Value::String(json)).unwrap();
+        assert_eq!(
+            derived.signing_hash().unwrap(),
+            data.signing_hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_mail_with_arrays() {
+        let data: Eip712TypedData = serde_json::from_str(MAIL_WITH_ARRAYS).unwrap();
+        assert_eq!(
+            data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person[] to,string contents)Person(string name,address[] wallets)"
+        );
+        assert_eq!(
+            data.type_hash("Mail").unwrap(),
+            h256("4bd8a9a2b93427bb184aca81e24beb30ffa3c747e2a33d4225ec08bf12e2e753")
+        );
+        assert_eq!(
+            data.type_hash("Person").unwrap(),
+            h256("fabfe1ed996349fc6027709802be19d047da1aa5d6894ff5f6486d92db2e6860")
+        );
+        assert_eq!(
+            data.hash_struct("Person", &data.message["from"]).unwrap(),
+            h256("9b4846dd48b866f0ac54d61b9b21a9e746f921cefa4ee94c4c0a1c49c774f67f")
+        );
+        assert_eq!(
+            data.hash_struct("Mail", &data.message).unwrap(),
+            h256("eb4221181ff3f1a83ea7313993ca9218496e424604ba9492bb4052c03d5c3df8")
+        );
+        assert_eq!(
+            data.signing_hash().unwrap(),
+            h256("a85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2")
+        );
+    }
+
+    #[test]
+    fn test_encode_value() {
+        let data = Eip712TypedData::default();
+        let encode = |type_: &str, value: Value| data.encode_value(type_, &value);
+
+        assert_eq!(
+            encode("uint8", json!(255)).unwrap(),
+            H256::from_low_u64_be(255)
+        );
+        assert_eq!(
+            encode("uint", json!("0x10")).unwrap(),
+            H256::from_low_u64_be(16)
+        );
+        assert!(encode("uint8", json!(256)).is_err());
+        assert_eq!(encode("int8", json!(-1)).unwrap(), H256::repeat_byte(0xff));
+        assert_eq!(encode("int8", json!("-128")).unwrap().as_bytes()[31], 0x80);
+        assert!(encode("int8", json!(128)).is_err());
+        assert_eq!(
+            encode("bool", json!(true)).unwrap(),
+            H256::from_low_u64_be(1)
+        );
+        assert_eq!(
+            encode("bytes2", json!("0xabcd")).unwrap().as_bytes()[..3],
+            [0xab, 0xcd, 0x00]
+        );
+        assert!(encode("bytes2", json!("0xab")).is_err());
+        assert_eq!(encode("bytes", json!("0x")).unwrap(), keccak([]));
+        assert!(encode("uint256[2]", json!([1])).is_err());
+        assert!(encode("Unknown", json!({})).is_err());
+    }
+}
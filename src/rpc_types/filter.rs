@@ -13,11 +13,15 @@
 // limitations under the License.
 
 use super::Log;
-use serde::de::Error;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error, IntoDeserializer, MapAccess, SeqAccess, Visitor};
 use serde::ser::Serialize;
 use serde::{Deserialize, Deserializer, Serializer};
-use serde_json::{from_value, Value};
+use serde_json::{json, Value};
+use std::fmt;
+use std::marker::PhantomData;
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::{BlockNumber, Data20, Data32, VariadicValue};
 
 /// Filter Address
@@ -98,21 +102,151 @@ impl<'de> Deserialize<'de> for FilterChanges {
     where
         D: Deserializer<'de>,
     {
-        let v: Value = Deserialize::deserialize(deserializer)?;
-        match v.clone() {
-            Value::Array(filter_change) => {
-                if filter_change.is_empty() {
-                    Ok(FilterChanges::Empty)
-                } else {
-                    from_value(v.clone())
-                        .map(FilterChanges::Logs)
-                        .or_else(|_| from_value(v).map(FilterChanges::Hashes))
-                        .map_err(|_| D::Error::custom("Invalid type."))
+        Ok(match Changes::deserialize(deserializer)? {
+            Changes::Logs(logs) => FilterChanges::Logs(logs),
+            Changes::Hashes(hashes) => FilterChanges::Hashes(hashes),
+            Changes::Empty => FilterChanges::Empty,
+        })
+    }
+}
+
+// The changes of a filter, decoded in one pass. The kind of the changes is
+// decided by the first element, a string for hashes and an object for logs.
+pub(crate) enum Changes<L> {
+    Logs(Vec<L>),
+    Hashes(Vec<Data32>),
+    Empty,
+}
+
+// The first element of the changes.
+enum FirstChange<L> {
+    Log(L),
+    Hash(Data32),
+}
+
+impl<'de, L> Deserialize<'de> for FirstChange<L>
+where
+    L: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FirstChangeVisitor<L>(PhantomData<L>);
+
+        impl<'de, L> Visitor<'de> for FirstChangeVisitor<L>
+        where
+            L: Deserialize<'de>,
+        {
+            type Value = FirstChange<L>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a log or a hash")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Data32::deserialize(value.into_deserializer()).map(FirstChange::Hash)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                L::deserialize(MapAccessDeserializer::new(map)).map(FirstChange::Log)
+            }
+        }
+
+        deserializer.deserialize_any(FirstChangeVisitor(PhantomData))
+    }
+}
+
+impl<'de, L> Deserialize<'de> for Changes<L>
+where
+    L: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ChangesVisitor<L>(PhantomData<L>);
+
+        impl<'de, L> Visitor<'de> for ChangesVisitor<L>
+        where
+            L: Deserialize<'de>,
+        {
+            type Value = Changes<L>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an array of logs or hashes")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Changes::Empty)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                match seq.next_element::<FirstChange<L>>()? {
+                    None => Ok(Changes::Empty),
+                    Some(FirstChange::Log(log)) => {
+                        let mut logs = vec![log];
+                        while let Some(log) = seq.next_element()? {
+                            logs.push(log);
+                        }
+                        Ok(Changes::Logs(logs))
+                    }
+                    Some(FirstChange::Hash(hash)) => {
+                        let mut hashes = vec![hash];
+                        while let Some(hash) = seq.next_element()? {
+                            hashes.push(hash);
+                        }
+                        Ok(Changes::Hashes(hashes))
+                    }
                 }
             }
-            Value::Null => Ok(FilterChanges::Empty),
-            _ => Err(D::Error::custom("Invalid type.")),
         }
+
+        deserializer.deserialize_any(ChangesVisitor(PhantomData))
+    }
+}
+
+impl OpenRpcSchema for Filter {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Filter", |components| {
+            object_schema(
+                "Filter",
+                vec![
+                    ("fromBlock", components.schema::<BlockNumber>()),
+                    ("toBlock", components.schema::<BlockNumber>()),
+                    ("address", components.schema::<Option<FilterAddress>>()),
+                    ("topics", components.schema::<Option<Vec<Topic>>>()),
+                    ("limit", components.schema::<usize>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for FilterChanges {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("FilterChanges", |components| {
+            json!({
+                "title": "FilterChanges",
+                "oneOf": [
+                    components.schema::<Vec<Log>>(),
+                    components.schema::<Vec<Data32>>(),
+                ],
+            })
+        })
     }
 }
 
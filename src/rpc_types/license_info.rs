@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use serde_json::Value;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct LicenseInfo {
     #[serde(rename = "licenseType")]
@@ -28,6 +31,30 @@ pub struct LicenseInfo {
     pub error_message: Option<String>,
 }
 
+impl OpenRpcSchema for LicenseInfo {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("LicenseInfo", |components| {
+            object_schema(
+                "LicenseInfo",
+                vec![
+                    ("licenseType", components.schema::<String>()),
+                    ("fingerPrint", components.schema::<Option<String>>()),
+                    ("expirationDate", components.schema::<Option<String>>()),
+                    ("issuer", components.schema::<Option<String>>()),
+                    ("errorMessage", components.schema::<Option<String>>()),
+                ],
+                &[
+                    "licenseType",
+                    "fingerPrint",
+                    "expirationDate",
+                    "issuer",
+                    "errorMessage",
+                ],
+            )
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::LicenseInfo;
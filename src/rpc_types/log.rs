@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::Data;
+use crate::rpc_types::{Data20, Data32, Quantity};
 use ethereum_types::{H160, H256, U256};
+use serde_json::Value;
 
 /// Log
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
@@ -44,6 +47,41 @@ pub struct Log {
     pub transaction_log_index: Option<U256>,
 }
 
+impl OpenRpcSchema for Log {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Log", |components| {
+            object_schema(
+                "Log",
+                vec![
+                    ("address", components.schema::<Data20>()),
+                    ("topics", components.schema::<Vec<Data32>>()),
+                    ("data", components.schema::<Data>()),
+                    ("blockHash", components.schema::<Option<Data32>>()),
+                    ("blockNumber", components.schema::<Option<Quantity>>()),
+                    ("transactionHash", components.schema::<Option<Data32>>()),
+                    ("transactionIndex", components.schema::<Option<Quantity>>()),
+                    ("logIndex", components.schema::<Option<Quantity>>()),
+                    (
+                        "transactionLogIndex",
+                        components.schema::<Option<Quantity>>(),
+                    ),
+                ],
+                &[
+                    "address",
+                    "topics",
+                    "data",
+                    "blockHash",
+                    "blockNumber",
+                    "transactionHash",
+                    "transactionIndex",
+                    "logIndex",
+                    "transactionLogIndex",
+                ],
+            )
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
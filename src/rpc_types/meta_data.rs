@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::{Data20, EconomicalModel, Quantity};
+use serde_json::Value;
 
 /// Metadata of current chain.
 ///
@@ -52,6 +54,46 @@ pub struct MetaData {
     pub economical_model: EconomicalModel,
 }
 
+impl OpenRpcSchema for MetaData {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("MetaData", |components| {
+            object_schema(
+                "MetaData",
+                vec![
+                    ("chainId", components.schema::<u32>()),
+                    ("chainIdV1", components.schema::<Quantity>()),
+                    ("chainName", components.schema::<String>()),
+                    ("operator", components.schema::<String>()),
+                    ("website", components.schema::<String>()),
+                    ("genesisTimestamp", components.schema::<u64>()),
+                    ("validators", components.schema::<Vec<Data20>>()),
+                    ("blockInterval", components.schema::<u64>()),
+                    ("tokenName", components.schema::<String>()),
+                    ("tokenSymbol", components.schema::<String>()),
+                    ("tokenAvatar", components.schema::<String>()),
+                    ("version", components.schema::<u32>()),
+                    ("economicalModel", components.schema::<EconomicalModel>()),
+                ],
+                &[
+                    "chainId",
+                    "chainIdV1",
+                    "chainName",
+                    "operator",
+                    "website",
+                    "genesisTimestamp",
+                    "validators",
+                    "blockInterval",
+                    "tokenName",
+                    "tokenSymbol",
+                    "tokenAvatar",
+                    "version",
+                    "economicalModel",
+                ],
+            )
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{EconomicalModel, MetaData};
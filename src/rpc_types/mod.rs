@@ -46,6 +46,7 @@ pub use self::call_request::CallRequest;
 pub use self::call_result::CallResult;
 pub use self::censor_addrs::CensorAddrs;
 pub use self::exchange::{BlockParamsByHash, BlockParamsByNumber, CountOrCode, RpcBlock};
+pub(crate) use self::filter::Changes;
 pub use self::filter::{Filter, FilterAddress, FilterChanges, Topic};
 pub use self::license_info::LicenseInfo;
 pub use self::log::Log;
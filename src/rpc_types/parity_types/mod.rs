@@ -14,8 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with OpenEthereum.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Ethereum Transactions
+//! Ethereum Transactions and Traces
 
+pub mod trace;
 mod transaction;
 mod transaction_id;
 
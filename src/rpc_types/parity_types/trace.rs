@@ -0,0 +1,472 @@
+// Copyright 2020-2020 Parity Technologies (UK) Ltd.
+// This file is part of OpenEthereum.
+
+// OpenEthereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// OpenEthereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with OpenEthereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Traces of the `trace_` namespace.
+
+use ethereum_types::{H160, H256, U256};
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data32;
+use crate::rpc_types::{BlockNumber, Data, Data20};
+
+/// Kind of the call.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CallType {
+    None,
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+}
+
+/// Kind of the reward.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum RewardType {
+    Block,
+    Uncle,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Call {
+    pub from: H160,
+    pub to: H160,
+    pub value: U256,
+    pub gas: U256,
+    pub input: Data,
+    pub call_type: CallType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Create {
+    pub from: H160,
+    pub value: U256,
+    pub gas: U256,
+    pub init: Data,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Suicide {
+    pub address: H160,
+    pub refund_address: H160,
+    pub balance: U256,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Reward {
+    pub author: H160,
+    pub value: U256,
+    pub reward_type: RewardType,
+}
+
+/// Action of a trace, serialized as the `type` and the `action`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "action", rename_all = "lowercase")]
+pub enum Action {
+    Call(Call),
+    Create(Create),
+    Suicide(Suicide),
+    Reward(Reward),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallResult {
+    pub gas_used: U256,
+    pub output: Data,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateResult {
+    pub gas_used: U256,
+    pub code: Data,
+    pub address: H160,
+}
+
+/// Result of a trace, the failed ones are serialized as the `error`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Res {
+    Call(CallResult),
+    Create(CreateResult),
+    FailedCall(String),
+    FailedCreate(String),
+    None,
+}
+
+// The `result` of a trace.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Output {
+    Call(CallResult),
+    Create(CreateResult),
+}
+
+// The trace as serialized.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTrace {
+    #[serde(flatten)]
+    action: Action,
+    // Absent for the failed traces, and `null` for the ones without output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Option<Output>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    trace_address: Vec<usize>,
+    subtraces: usize,
+}
+
+/// A trace of a call, `traceAddress` is its path in the call tree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawTrace", into = "RawTrace")]
+pub struct Trace {
+    pub action: Action,
+    pub result: Res,
+    pub trace_address: Vec<usize>,
+    pub subtraces: usize,
+}
+
+impl TryFrom<RawTrace> for Trace {
+    type Error = String;
+
+    fn try_from(raw: RawTrace) -> Result<Self, Self::Error> {
+        let result = match (&raw.action, raw.result.flatten(), raw.error) {
+            (Action::Create(_), None, Some(error)) => Res::FailedCreate(error),
+            (_, None, Some(error)) => Res::FailedCall(error),
+            (_, Some(_), Some(_)) => {
+                return Err("a trace has both result and error".to_owned());
+            }
+            (Action::Call(_), Some(Output::Call(result)), None) => Res::Call(result),
+            (Action::Create(_), Some(Output::Create(result)), None) => Res::Create(result),
+            (_, None, None) => Res::None,
+            (_, Some(_), None) => {
+                return Err("the result does not match the action".to_owned());
+            }
+        };
+        Ok(Trace {
+            action: raw.action,
+            result,
+            trace_address: raw.trace_address,
+            subtraces: raw.subtraces,
+        })
+    }
+}
+
+impl From<Trace> for RawTrace {
+    fn from(trace: Trace) -> Self {
+        let (result, error) = match trace.result {
+            Res::Call(result) => (Some(Some(Output::Call(result))), None),
+            Res::Create(result) => (Some(Some(Output::Create(result))), None),
+            Res::FailedCall(error) | Res::FailedCreate(error) => (None, Some(error)),
+            Res::None => (Some(None), None),
+        };
+        RawTrace {
+            action: trace.action,
+            result,
+            error,
+            trace_address: trace.trace_address,
+            subtraces: trace.subtraces,
+        }
+    }
+}
+
+/// A trace with its location in the chain, the rewards have no transaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+    #[serde(flatten)]
+    pub trace: Trace,
+    pub transaction_position: Option<usize>,
+    pub transaction_hash: Option<H256>,
+    pub block_number: u64,
+    pub block_hash: H256,
+}
+
+/// Kind of the traces of `trace_call` and `trace_replayTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceType {
+    Trace,
+    VmTrace,
+    StateDiff,
+}
+
+/// Result of `trace_call` and `trace_replayTransaction`, the VM trace and the
+/// state diff are forwarded as they are.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+    pub output: Data,
+    pub trace: Vec<Trace>,
+    pub vm_trace: Option<Value>,
+    pub state_diff: Option<Value>,
+}
+
+/// Filter of `trace_filter`, `after` and `count` paginate the matched traces.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumber>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumber>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<Vec<Data20>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_address: Option<Vec<Data20>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+impl TraceFilter {
+    /// Whether the addresses of the trace match the filter, the blocks are
+    /// not checked.
+    ///
+    /// The sender is the `from` of calls and creations, the contract of
+    /// suicides and the author of rewards. The receiver is the `to` of calls,
+    /// the created contract and the refund address of suicides.
+    pub fn matches(&self, trace: &Trace) -> bool {
+        let (from, to) = match (&trace.action, &trace.result) {
+            (Action::Call(call), _) => (call.from, Some(call.to)),
+            (Action::Create(create), Res::Create(result)) => (create.from, Some(result.address)),
+            (Action::Create(create), _) => (create.from, None),
+            (Action::Suicide(suicide), _) => (suicide.address, Some(suicide.refund_address)),
+            (Action::Reward(reward), _) => (reward.author, None),
+        };
+        let contains = |addresses: &Option<Vec<Data20>>, address: Option<H160>| match addresses {
+            None => true,
+            Some(addresses) if addresses.is_empty() => true,
+            Some(addresses) => address.is_some_and(|address| addresses.contains(&address.into())),
+        };
+        contains(&self.from_address, Some(from)) && contains(&self.to_address, to)
+    }
+}
+
+// The properties of `Trace`, which are flattened into `LocalizedTrace`.
+fn trace_properties(components: &mut OpenRpcComponents) -> Vec<(&'static str, Value)> {
+    vec![
+        (
+            "type",
+            json!({ "type": "string", "enum": ["call", "create", "suicide", "reward"] }),
+        ),
+        ("action", json!({ "type": "object" })),
+        (
+            "result",
+            json!({ "oneOf": [{ "type": "null" }, { "type": "object" }] }),
+        ),
+        ("error", components.schema::<String>()),
+        ("traceAddress", components.schema::<Vec<usize>>()),
+        ("subtraces", components.schema::<usize>()),
+    ]
+}
+
+impl OpenRpcSchema for Trace {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Trace", |components| {
+            object_schema(
+                "Trace",
+                trace_properties(components),
+                &["action", "type", "traceAddress", "subtraces"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for LocalizedTrace {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("LocalizedTrace", |components| {
+            let mut properties = trace_properties(components);
+            properties.push(("transactionPosition", components.schema::<Option<usize>>()));
+            properties.push(("transactionHash", components.schema::<Option<Data32>>()));
+            properties.push(("blockNumber", components.schema::<u64>()));
+            properties.push(("blockHash", components.schema::<Data32>()));
+            object_schema(
+                "LocalizedTrace",
+                properties,
+                &[
+                    "action",
+                    "type",
+                    "traceAddress",
+                    "subtraces",
+                    "blockNumber",
+                    "blockHash",
+                ],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for TraceType {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TraceType", |_| {
+            json!({
+                "title": "TraceType",
+                "type": "string",
+                "enum": ["trace", "vmTrace", "stateDiff"],
+            })
+        })
+    }
+}
+
+impl OpenRpcSchema for TraceResults {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TraceResults", |components| {
+            object_schema(
+                "TraceResults",
+                vec![
+                    ("output", components.schema::<Data>()),
+                    ("trace", components.schema::<Vec<Trace>>()),
+                    ("vmTrace", components.schema::<Value>()),
+                    ("stateDiff", components.schema::<Value>()),
+                ],
+                &["output", "trace", "vmTrace", "stateDiff"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for TraceFilter {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TraceFilter", |components| {
+            object_schema(
+                "TraceFilter",
+                vec![
+                    ("fromBlock", components.schema::<BlockNumber>()),
+                    ("toBlock", components.schema::<BlockNumber>()),
+                    ("fromAddress", components.schema::<Vec<Data20>>()),
+                    ("toAddress", components.schema::<Vec<Data20>>()),
+                    ("after", components.schema::<usize>()),
+                    ("count", components.schema::<usize>()),
+                ],
+                &[],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_trace() -> Value {
+        json!({
+            "action": {
+                "from": "0x000000000000000000000000000000000000000a",
+                "to": "0x000000000000000000000000000000000000000b",
+                "value": "0x1",
+                "gas": "0x5208",
+                "input": "0x",
+                "callType": "call"
+            },
+            "result": {"gasUsed": "0x0", "output": "0x"},
+            "traceAddress": [],
+            "subtraces": 1,
+            "type": "call",
+            "transactionPosition": 0,
+            "transactionHash": "0x000000000000000000000000000000000000000000000000000000000000000c",
+            "blockNumber": 16,
+            "blockHash": "0x000000000000000000000000000000000000000000000000000000000000000d"
+        })
+    }
+
+    #[test]
+    fn test_localized_trace() {
+        let json = call_trace();
+        let trace: LocalizedTrace = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(trace.block_number, 16);
+        assert_eq!(
+            trace.trace.result,
+            Res::Call(CallResult {
+                gas_used: U256::zero(),
+                output: Data::new(vec![]),
+            })
+        );
+        match trace.trace.action {
+            Action::Call(ref call) => assert_eq!(call.call_type, CallType::Call),
+            _ => panic!("should be a call"),
+        }
+        assert_eq!(serde_json::to_value(&trace).unwrap(), json);
+    }
+
+    #[test]
+    fn test_failed_and_reward_traces() {
+        let create = json!({
+            "action": {
+                "from": "0x000000000000000000000000000000000000000a",
+                "value": "0x0",
+                "gas": "0x5208",
+                "init": "0x6000"
+            },
+            "error": "Out of gas",
+            "traceAddress": [0],
+            "subtraces": 0,
+            "type": "create"
+        });
+        let trace: Trace = serde_json::from_value(create.clone()).unwrap();
+        assert_eq!(trace.result, Res::FailedCreate("Out of gas".to_owned()));
+        assert_eq!(serde_json::to_value(&trace).unwrap(), create);
+
+        let reward = json!({
+            "action": {
+                "author": "0x000000000000000000000000000000000000000a",
+                "value": "0x1bc16d674ec80000",
+                "rewardType": "block"
+            },
+            "result": null,
+            "traceAddress": [],
+            "subtraces": 0,
+            "type": "reward"
+        });
+        let trace: Trace = serde_json::from_value(reward.clone()).unwrap();
+        assert_eq!(trace.result, Res::None);
+        assert_eq!(serde_json::to_value(&trace).unwrap(), reward);
+
+        let mut mismatched = create;
+        mismatched["result"] = json!({"gasUsed": "0x0", "output": "0x"});
+        mismatched.as_object_mut().unwrap().remove("error");
+        assert!(serde_json::from_value::<Trace>(mismatched).is_err());
+    }
+
+    #[test]
+    fn test_trace_filter() {
+        let trace: LocalizedTrace = serde_json::from_value(call_trace()).unwrap();
+        let trace = trace.trace;
+        let filter: TraceFilter = serde_json::from_value(json!({
+            "fromBlock": "0x1",
+            "toAddress": ["0x000000000000000000000000000000000000000b"],
+            "count": 10
+        }))
+        .unwrap();
+        assert!(filter.matches(&trace));
+        assert!(TraceFilter::default().matches(&trace));
+
+        let filter = TraceFilter {
+            from_address: Some(vec![H160::from_low_u64_be(11).into()]),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&trace));
+    }
+}
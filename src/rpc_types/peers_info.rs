@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Quantity;
 use ethereum_types::Address;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -25,6 +29,13 @@ pub struct PeersInfo {
     pub extra_info: Option<HashMap<Address, ExtraInfo>>,
 }
 
+impl PeersInfo {
+    /// The result of `net_peerCount`.
+    pub fn peer_count(&self) -> Quantity {
+        Quantity::from(u64::from(self.amount))
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ExtraInfo {
     pub node_type: String,
@@ -32,6 +43,46 @@ pub struct ExtraInfo {
     pub soft_version: String,
 }
 
+impl OpenRpcSchema for PeersInfo {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("PeersInfo", |components| {
+            // The maps are keyed by the address of the peer.
+            object_schema(
+                "PeersInfo",
+                vec![
+                    ("amount", components.schema::<u32>()),
+                    (
+                        "peers",
+                        components.schema::<Option<BTreeMap<String, String>>>(),
+                    ),
+                    ("errorMessage", components.schema::<Option<String>>()),
+                    (
+                        "extra_info",
+                        components.schema::<BTreeMap<String, ExtraInfo>>(),
+                    ),
+                ],
+                &["amount", "peers", "errorMessage"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for ExtraInfo {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("ExtraInfo", |components| {
+            object_schema(
+                "ExtraInfo",
+                vec![
+                    ("node_type", components.schema::<String>()),
+                    ("height", components.schema::<u64>()),
+                    ("soft_version", components.schema::<String>()),
+                ],
+                &["node_type", "height", "soft_version"],
+            )
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::PeersInfo;
@@ -85,6 +136,10 @@ mod tests {
             extra_info: None,
         };
 
-        assert_eq!(serde_json::to_value(peers_info).unwrap(), value);
+        assert_eq!(serde_json::to_value(&peers_info).unwrap(), value);
+        assert_eq!(
+            serde_json::to_value(peers_info.peer_count()).unwrap(),
+            json!("0x0")
+        );
     }
 }
@@ -1,4 +1,19 @@
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use serde_json::Value;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct PoolTxNum {
     pub num: u64,
 }
+
+impl OpenRpcSchema for PoolTxNum {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("PoolTxNum", |components| {
+            object_schema(
+                "PoolTxNum",
+                vec![("num", components.schema::<u64>())],
+                &["num"],
+            )
+        })
+    }
+}
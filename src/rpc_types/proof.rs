@@ -14,7 +14,11 @@
 
 use std::collections::HashMap;
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data32;
 use ethereum_types::{Address, H256};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Proof {
@@ -29,3 +33,39 @@ pub struct BftProof {
     pub round: usize,
     pub commits: HashMap<Address, String>,
 }
+
+impl OpenRpcSchema for Proof {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Proof", |components| {
+            json!({
+                "title": "Proof",
+                "oneOf": [
+                    { "type": "string", "enum": ["Raft"] },
+                    object_schema(
+                        "Bft",
+                        vec![("Bft", components.schema::<BftProof>())],
+                        &["Bft"],
+                    ),
+                ],
+            })
+        })
+    }
+}
+
+impl OpenRpcSchema for BftProof {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BftProof", |components| {
+            object_schema(
+                "BftProof",
+                vec![
+                    ("proposal", components.schema::<Data32>()),
+                    ("height", components.schema::<usize>()),
+                    ("round", components.schema::<usize>()),
+                    // Keyed by the address of the validator.
+                    ("commits", components.schema::<BTreeMap<String, String>>()),
+                ],
+                &["proposal", "height", "round", "commits"],
+            )
+        })
+    }
+}
@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::log::Log;
+use crate::rpc_types::{Data, Data20, Data32, Quantity};
 use ethereum_types::{Bloom, H160, H256, U256};
+use serde_json::Value;
 
 /// Receipt
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -52,6 +55,42 @@ pub struct Receipt {
     pub error_message: Option<String>,
 }
 
+impl OpenRpcSchema for Receipt {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("Receipt", |components| {
+            object_schema(
+                "Receipt",
+                vec![
+                    ("transactionHash", components.schema::<Option<Data32>>()),
+                    ("transactionIndex", components.schema::<Option<Quantity>>()),
+                    ("blockHash", components.schema::<Option<Data32>>()),
+                    ("blockNumber", components.schema::<Option<Quantity>>()),
+                    ("cumulativeQuotaUsed", components.schema::<Quantity>()),
+                    ("quotaUsed", components.schema::<Option<Quantity>>()),
+                    ("contractAddress", components.schema::<Option<Data20>>()),
+                    ("logs", components.schema::<Vec<Log>>()),
+                    ("root", components.schema::<Option<Data32>>()),
+                    ("logsBloom", components.schema::<Data>()),
+                    ("errorMessage", components.schema::<Option<String>>()),
+                ],
+                &[
+                    "transactionHash",
+                    "transactionIndex",
+                    "blockHash",
+                    "blockNumber",
+                    "cumulativeQuotaUsed",
+                    "quotaUsed",
+                    "contractAddress",
+                    "logs",
+                    "root",
+                    "logsBloom",
+                    "errorMessage",
+                ],
+            )
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
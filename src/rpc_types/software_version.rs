@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use serde_json::Value;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SoftwareVersion {
     #[serde(rename = "softwareVersion")]
@@ -24,6 +27,23 @@ impl SoftwareVersion {
             software_version: version,
         }
     }
+
+    /// The result of `web3_clientVersion`, such as `CITA/v0.22.0`.
+    pub fn client_version(&self) -> String {
+        format!("CITA/v{}", self.software_version.trim_start_matches('v'))
+    }
+}
+
+impl OpenRpcSchema for SoftwareVersion {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("SoftwareVersion", |components| {
+            object_schema(
+                "SoftwareVersion",
+                vec![("softwareVersion", components.schema::<String>())],
+                &["softwareVersion"],
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -39,4 +59,12 @@ mod tests {
         let software_version = SoftwareVersion::new("0.22.0".to_owned());
         assert_eq!(serde_json::to_value(software_version).unwrap(), value);
     }
+
+    #[test]
+    fn software_version_client_version() {
+        let software_version = SoftwareVersion::new("0.22.0".to_owned());
+        assert_eq!(software_version.client_version(), "CITA/v0.22.0");
+        let software_version = SoftwareVersion::new("v6.7.0".to_owned());
+        assert_eq!(software_version.client_version(), "CITA/v6.7.0");
+    }
 }
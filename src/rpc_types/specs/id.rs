@@ -12,25 +12,219 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(untagged)]
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+
+// The name used by `serde_json` to hand out the raw text of a value, as
+// `RawValue` does.
+const RAW_VALUE_TOKEN: &str = "$serde_json::private::RawValue";
+
+/// Request id, numeric ids are kept exactly as received.
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum Id {
     /// No id (notification)
+    #[default]
     Null,
     /// String id
     Str(String),
     /// Numeric id
     Num(u64),
-}
-
-impl Default for Id {
-    fn default() -> Self {
-        Id::Null
-    }
+    /// Any other numeric id (negative, fractional or out of range), kept as the
+    /// JSON text received
+    Number(String),
 }
 
 impl Id {
     pub fn is_null(&self) -> bool {
         *self == Id::Null
     }
+
+    fn from_raw(raw: &str) -> Result<Self, String> {
+        match raw.bytes().next() {
+            Some(b'n') if raw == "null" => Ok(Id::Null),
+            Some(b'"') => serde_json::from_str(raw)
+                .map(Id::Str)
+                .map_err(|err| err.to_string()),
+            Some(b'-') | Some(b'0'..=b'9') => Ok(raw
+                .parse::<u64>()
+                .map(Id::Num)
+                .unwrap_or_else(|_| Id::Number(raw.to_owned()))),
+            _ => Err(format!("invalid id `{}`", raw)),
+        }
+    }
+}
+
+impl Serialize for Id {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Id::Null => serializer.serialize_unit(),
+            Id::Str(ref s) => serializer.serialize_str(s),
+            Id::Num(n) => serializer.serialize_u64(n),
+            Id::Number(ref n) => {
+                // The numbers which survive a native number are serialized as
+                // one, the others are kept as the raw text for `serde_json`.
+                if let Ok(n) = n.parse::<i64>() {
+                    serializer.serialize_i64(n)
+                } else if let Some(f) = n.parse::<f64>().ok().filter(|f| f.to_string() == *n) {
+                    serializer.serialize_f64(f)
+                } else {
+                    RawValue::from_string(n.clone())
+                        .map_err(serde::ser::Error::custom)?
+                        .serialize(serializer)
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D>(deserializer: D) -> Result<Id, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // `serde_json` gives the raw text of the id, which keeps the numeric
+        // ids exactly as received. The buffered content of serde and the other
+        // deserializers fall back to `deserialize_any`.
+        deserializer.deserialize_newtype_struct(RAW_VALUE_TOKEN, IdVisitor)
+    }
+}
+
+struct IdVisitor;
+
+impl<'de> Visitor<'de> for IdVisitor {
+    type Value = Id;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string, a number or null")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Id, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Id, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key::<String>()? {
+            Some(ref key) if key == RAW_VALUE_TOKEN => {
+                let raw: String = map.next_value()?;
+                Id::from_raw(&raw).map_err(de::Error::custom)
+            }
+            _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+        }
+    }
+
+    fn visit_unit<E>(self) -> Result<Id, E> {
+        Ok(Id::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Id, E> {
+        Ok(Id::Null)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Id, E> {
+        Ok(Id::Str(value.to_owned()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Id, E> {
+        Ok(Id::Str(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Id, E> {
+        Ok(Id::Num(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Id, E> {
+        if value >= 0 {
+            Ok(Id::Num(value as u64))
+        } else {
+            Ok(Id::Number(value.to_string()))
+        }
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Id, E> {
+        Ok(Id::Number(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Id;
+
+    #[test]
+    fn test_id_round_trip() {
+        let testdata = vec![
+            ("null", Id::Null),
+            (r#""abc""#, Id::Str("abc".to_owned())),
+            (r#""a\"b""#, Id::Str(r#"a"b"#.to_owned())),
+            ("0", Id::Num(0)),
+            ("18446744073709551615", Id::Num(u64::MAX)),
+            (
+                "18446744073709551616",
+                Id::Number("18446744073709551616".to_owned()),
+            ),
+            ("-1", Id::Number("-1".to_owned())),
+            ("1.50", Id::Number("1.50".to_owned())),
+            ("-1.0e-10", Id::Number("-1.0e-10".to_owned())),
+        ];
+        for (data, expected) in testdata.into_iter() {
+            let id: Id = serde_json::from_str(data).unwrap();
+            assert_eq!(id, expected);
+            assert_eq!(serde_json::to_string(&id).unwrap(), data);
+        }
+        for data in ["true", "{}", "[1]"].iter() {
+            assert!(serde_json::from_str::<Id>(data).is_err());
+        }
+    }
+
+    #[test]
+    fn test_id_from_value() {
+        let id: Id = serde_json::from_value(json!(-2)).unwrap();
+        assert_eq!(id, Id::Number("-2".to_owned()));
+        assert_eq!(serde_json::to_value(&id).unwrap(), json!(-2));
+        let id: Id = serde_json::from_value(json!(7)).unwrap();
+        assert_eq!(id, Id::Num(7));
+        let id: Id = serde_json::from_value(json!(null)).unwrap();
+        assert_eq!(id, Id::Null);
+    }
+
+    #[test]
+    fn test_id_in_buffered_content() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(untagged)]
+        enum Wrapped {
+            Id(Id),
+        }
+
+        let testdata = vec![
+            ("null", Id::Null),
+            (r#""abc""#, Id::Str("abc".to_owned())),
+            ("7", Id::Num(7)),
+            ("-2", Id::Number("-2".to_owned())),
+            ("1.5", Id::Number("1.5".to_owned())),
+        ];
+        for (data, expected) in testdata.into_iter() {
+            let wrapped: Wrapped = serde_json::from_str(data).unwrap();
+            assert_eq!(wrapped, Wrapped::Id(expected));
+        }
+    }
+
+    #[test]
+    fn test_id_from_reader() {
+        for data in ["null", r#""abc""#, "7", "-2", "1.50"].iter() {
+            let id: Id = serde_json::from_reader(data.as_bytes()).unwrap();
+            assert_eq!(id, serde_json::from_str::<Id>(data).unwrap());
+            assert_eq!(serde_json::to_string(&id).unwrap(), *data);
+        }
+    }
 }
@@ -14,7 +14,10 @@
 
 use ethereum_types::{Address, H256, U256};
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
 use crate::rpc_types::Data;
+use crate::rpc_types::{Data20, Data32, Quantity};
+use serde_json::{json, Value};
 
 // TODO: No need Deserialize. Just because test in trans.rs
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -42,3 +45,59 @@ pub enum BlockTransaction {
     Full(FullTransaction),
     Hash(H256),
 }
+
+impl OpenRpcSchema for FullTransaction {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("FullTransaction", |components| {
+            object_schema(
+                "FullTransaction",
+                vec![
+                    ("hash", components.schema::<Data32>()),
+                    ("content", components.schema::<Data>()),
+                    ("from", components.schema::<Data20>()),
+                ],
+                &["hash", "content", "from"],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for RpcTransaction {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("RpcTransaction", |components| {
+            object_schema(
+                "RpcTransaction",
+                vec![
+                    ("hash", components.schema::<Data32>()),
+                    ("content", components.schema::<Data>()),
+                    ("from", components.schema::<Data20>()),
+                    ("blockNumber", components.schema::<Quantity>()),
+                    ("blockHash", components.schema::<Data32>()),
+                    ("index", components.schema::<Quantity>()),
+                ],
+                &[
+                    "hash",
+                    "content",
+                    "from",
+                    "blockNumber",
+                    "blockHash",
+                    "index",
+                ],
+            )
+        })
+    }
+}
+
+impl OpenRpcSchema for BlockTransaction {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("BlockTransaction", |components| {
+            json!({
+                "title": "BlockTransaction",
+                "oneOf": [
+                    components.schema::<FullTransaction>(),
+                    components.schema::<Data32>(),
+                ],
+            })
+        })
+    }
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::rpc_request::{object_schema, OpenRpcComponents, OpenRpcSchema};
+use crate::rpc_types::Data32;
 use ethereum_types::H256;
+use serde_json::Value;
 
 //TODO respone contain error
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -26,3 +29,18 @@ impl TxResponse {
         TxResponse { hash, status }
     }
 }
+
+impl OpenRpcSchema for TxResponse {
+    fn schema(components: &mut OpenRpcComponents) -> Value {
+        components.reference("TxResponse", |components| {
+            object_schema(
+                "TxResponse",
+                vec![
+                    ("hash", components.schema::<Data32>()),
+                    ("status", components.schema::<String>()),
+                ],
+                &["hash", "status"],
+            )
+        })
+    }
+}